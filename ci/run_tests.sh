@@ -18,14 +18,14 @@ set -e
 echo "Is Rust from nightly: $IS_NIGHTLY"
 
 echo "Testing for asmjs-unknown-emscripten..."
-cargo web test --features web_test --target=asmjs-unknown-emscripten
+cargo web test --features "web_test ssr" --target=asmjs-unknown-emscripten
 
 echo "Testing for wasm32-unknown-emscripten..."
-cargo web test --features web_test --target=wasm32-unknown-emscripten
+cargo web test --features "web_test ssr" --target=wasm32-unknown-emscripten
 
 if [ "$IS_NIGHTLY" = "1" ]; then
     echo "Testing for wasm32-unknown-unknown..."
-    cargo web test --nodejs --target=wasm32-unknown-unknown
+    cargo web test --features ssr --nodejs --target=wasm32-unknown-unknown
 fi
 
 check_example() {
@@ -0,0 +1,258 @@
+//! Attributes of tags: special attributes (`class`, `value`, `ref`, ..),
+//! listeners, DOM properties and plain attributes.
+
+use proc_macro2::{Ident, TokenStream};
+use quote::{quote, quote_spanned, ToTokens};
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream, Result};
+use syn::spanned::Spanned;
+use syn::{parenthesized, token, Error, Expr, Pat, PatType, Token, Type};
+
+use crate::node::{local, ToStack};
+use crate::tag::TagName;
+use crate::value::parse_value;
+
+/// Listeners which are implemented in `yew::html` module. Other names which start
+/// with `on` are plain attributes, the `(onX)=` form is always a listener.
+const LISTENERS: &[&str] = &[
+    "onclick", "ondoubleclick", "onkeypress", "onkeydown", "onkeyup", "onmousemove",
+    "onmousedown", "onmouseup", "onmouseover", "onmouseout", "onmouseenter", "onmouseleave",
+    "onmousewheel", "ongotpointercapture", "onlostpointercapture", "onpointercancel",
+    "onpointerdown", "onpointerenter", "onpointerleave", "onpointermove", "onpointerout",
+    "onpointerover", "onpointerup", "onscroll", "onblur", "onfocus", "onsubmit", "ondragstart",
+    "ondrag", "ondragend", "ondragenter", "ondragleave", "ondragover", "ondragexit", "ondrop",
+    "oncontextmenu", "ontouchstart", "ontouchmove", "ontouchend", "ontouchcancel",
+    "onanimationstart", "onanimationend", "onanimationiteration", "ontransitionend", "oncopy",
+    "oncut", "onpaste", "oncompositionstart", "oncompositionupdate", "oncompositionend",
+    "onload", "onerror", "oninput", "onchange",
+];
+
+pub enum TagAttribute {
+    Namespace(Expr),
+    Key(Expr),
+    Ref(Expr),
+    Classes(Vec<Expr>),
+    Class(Expr),
+    Style(Expr),
+    Value(Expr),
+    Kind(Expr),
+    Checked(Expr),
+    Href(Expr),
+    Props(Expr),
+    Prop(Ident, Expr),
+    Listener(Ident, Vec<Ident>, Expr),
+    Custom(TagName, Vec<Ident>, Expr),
+    Attribute(TagName, Expr),
+}
+
+impl Parse for TagAttribute {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.peek(token::Paren) {
+            // The escape form of a listener: `(onclick)=self.handler()`
+            let content;
+            parenthesized!(content in input);
+            let action: Ident = content.parse()?;
+            input.parse::<Token![=]>()?;
+            let handler = parse_value(input)?;
+            return Ok(TagAttribute::Listener(action, Vec::new(), handler));
+        }
+        let name: TagName = input.parse()?;
+        if input.peek(Token![:]) && !input.peek(Token![::]) {
+            let colon: Token![:] = input.parse()?;
+            let prefix = name.to_string();
+            return match prefix.as_str() {
+                "on" => {
+                    let event = input.parse()?;
+                    let modifiers = parse_modifiers(input)?;
+                    input.parse::<Token![=]>()?;
+                    let handler = parse_value(input)?;
+                    Ok(TagAttribute::Custom(event, modifiers, handler))
+                }
+                "prop" => {
+                    let prop = Ident::parse_any(input)?;
+                    input.parse::<Token![=]>()?;
+                    let value = parse_value(input)?;
+                    Ok(TagAttribute::Prop(prop, value))
+                }
+                _ => {
+                    let message = "unknown prefix of the attribute, expected `on:` or `prop:`";
+                    Err(Error::new_spanned(quote! { #name #colon }, message))
+                }
+            };
+        }
+        let modifiers = parse_modifiers(input)?;
+        input.parse::<Token![=]>()?;
+        let value = parse_value(input)?;
+        // A handler of a wrong type is reported by the compiler with the span of the name
+        let listener = name
+            .single()
+            .filter(|name| LISTENERS.contains(&name.to_string().as_str()));
+        if let Some(listener) = listener {
+            return Ok(TagAttribute::Listener(listener.clone(), modifiers, value));
+        }
+        if let Some(modifier) = modifiers.first() {
+            return Err(Error::new_spanned(modifier, "modifiers are allowed for listeners only"));
+        }
+        let attribute = match name.to_string().as_str() {
+            "xmlns" => TagAttribute::Namespace(value),
+            "key" => TagAttribute::Key(value),
+            "ref" => TagAttribute::Ref(value),
+            "class" => match value {
+                // A list of classes: class=("class-1", "class-2", local_variable)
+                Expr::Tuple(tuple) => TagAttribute::Classes(tuple.elems.into_iter().collect()),
+                Expr::Paren(paren) => TagAttribute::Classes(vec![*paren.expr]),
+                value => TagAttribute::Class(value),
+            },
+            "style" => TagAttribute::Style(value),
+            "value" => TagAttribute::Value(value),
+            // `type` is a keyword in Rust, the kind of an `input` is kept separately
+            "type" => TagAttribute::Kind(value),
+            "checked" => TagAttribute::Checked(value),
+            "href" => TagAttribute::Href(value),
+            "props" => TagAttribute::Props(value),
+            _ => TagAttribute::Attribute(name, value),
+        };
+        Ok(attribute)
+    }
+}
+
+impl ToStack for TagAttribute {
+    fn to_stack(&self, stack: &Ident) -> TokenStream {
+        match *self {
+            TagAttribute::Namespace(ref value) => quote! {
+                ::yew::macros::set_namespace(&mut #stack, ::std::option::Option::Some(::std::convert::Into::into(#value)));
+            },
+            TagAttribute::Key(ref value) => quote! {
+                ::yew::macros::set_key(&mut #stack, #value);
+            },
+            TagAttribute::Ref(ref value) => quote! {
+                ::yew::macros::set_node_ref(&mut #stack, #value);
+            },
+            TagAttribute::Classes(ref classes) => quote! {
+                #( ::yew::macros::append_class(&mut #stack, #classes); )*
+            },
+            TagAttribute::Class(ref value) => quote! {
+                ::yew::macros::set_classes(&mut #stack, #value);
+            },
+            TagAttribute::Style(ref value) => quote! {
+                ::yew::macros::set_styles(&mut #stack, #value);
+            },
+            TagAttribute::Value(ref value) => quote! {
+                ::yew::macros::set_value_or_attribute(&mut #stack, #value);
+            },
+            TagAttribute::Kind(ref value) => quote! {
+                ::yew::macros::set_kind(&mut #stack, #value);
+            },
+            TagAttribute::Checked(ref value) => quote! {
+                ::yew::macros::set_checked(&mut #stack, #value);
+            },
+            TagAttribute::Href(ref value) => {
+                let href = local("href");
+                quote! {
+                    let #href: ::yew::html::Href = ::std::convert::Into::into(#value);
+                    ::yew::macros::add_attribute(&mut #stack, "href", #href);
+                }
+            }
+            TagAttribute::Props(ref value) => quote! {
+                ::yew::macros::set_props(&mut #stack, #value);
+            },
+            TagAttribute::Prop(ref name, ref value) => {
+                let name = name.unraw().to_string();
+                quote! {
+                    ::yew::macros::set_prop(&mut #stack, #name, #value);
+                }
+            }
+            TagAttribute::Listener(ref action, ref modifiers, ref handler) => {
+                // The type of the event is taken from the module of the action,
+                // unknown actions are reported with the span of the name
+                let event = quote_spanned! { action.span()=> ::yew::html::#action::Event };
+                let wrapper = quote_spanned! { action.span()=> ::yew::html::#action::Wrapper };
+                let handler_span = handler.span();
+                let handler = typed_handler(handler, event);
+                let (listener, options) = (local("listener"), local("options"));
+                let options_value = listener_options(modifiers);
+                let wrap = quote_spanned! { handler_span=> #wrapper::from(#handler) };
+                let attach = quote_spanned! { handler_span=>
+                    ::yew::macros::attach_listener(&mut #stack, ::std::boxed::Box::new(#listener));
+                };
+                quote! {
+                    let #options = #options_value;
+                    let #listener = #wrap.with_options(#options);
+                    #attach
+                }
+            }
+            TagAttribute::Custom(ref event, ref modifiers, ref handler) => {
+                let event_type = event.to_string();
+                let handler = typed_handler(handler, quote! { ::yew::events::CustomEvent });
+                let (listener, options) = (local("listener"), local("options"));
+                let options_value = listener_options(modifiers);
+                quote! {
+                    let #options = #options_value;
+                    let #listener = ::yew::html::CustomListener::new(#event_type, #handler).with_options(#options);
+                    ::yew::macros::attach_listener(&mut #stack, ::std::boxed::Box::new(#listener));
+                }
+            }
+            TagAttribute::Attribute(ref name, ref value) => {
+                let name = name.to_string();
+                let attribute = local("attribute");
+                quote! {
+                    // `OptionalAttribute` is used for `Option`s, `BooleanAttribute`
+                    // for `bool`s and `PlainAttribute` for other values
+                    let #attribute = {
+                        #[allow(unused_imports)]
+                        use ::yew::macros::{BooleanAttribute, OptionalAttribute, PlainAttribute};
+                        (&&::yew::macros::AttributeValue(#value)).to_attribute()
+                    };
+                    ::yew::macros::set_attribute(&mut #stack, #name, #attribute);
+                }
+            }
+        }
+    }
+}
+
+/// Parses modifiers of a listener: `.prevent.stop`.
+fn parse_modifiers(input: ParseStream) -> Result<Vec<Ident>> {
+    let mut modifiers = Vec::new();
+    while input.peek(Token![.]) {
+        input.parse::<Token![.]>()?;
+        modifiers.push(Ident::parse_any(input)?);
+    }
+    Ok(modifiers)
+}
+
+/// Every modifier is a method of `ListenerOptions`.
+fn listener_options(modifiers: &[Ident]) -> TokenStream {
+    let modifiers = modifiers.iter().map(|modifier| {
+        quote_spanned! { modifier.span()=> .#modifier() }
+    });
+    quote! { ::yew::virtual_dom::ListenerOptions::default() #(#modifiers)* }
+}
+
+/// Adds `move` and the type of the event to a closure: `|e| ..` becomes `move |e: Event| ..`.
+/// Types set explicitly are kept, they are checked when the listener is created.
+fn typed_handler(handler: &Expr, event: TokenStream) -> TokenStream {
+    let mut closure = match *handler {
+        Expr::Closure(ref closure) => closure.clone(),
+        ref handler => return handler.into_token_stream(),
+    };
+    if closure.capture.is_none() {
+        closure.capture = Some(Token![move](closure.or1_token.span()));
+    }
+    if closure.inputs.len() == 1 {
+        let input = closure.inputs.pop().expect("closure has an argument").into_value();
+        let input = match input {
+            Pat::Type(typed) => Pat::Type(typed),
+            pat => {
+                let span = pat.span();
+                Pat::Type(PatType {
+                    attrs: Vec::new(),
+                    pat: Box::new(pat),
+                    colon_token: Token![:](span),
+                    ty: Box::new(Type::Verbatim(event)),
+                })
+            }
+        };
+        closure.inputs.push(input);
+    }
+    closure.into_token_stream()
+}
@@ -0,0 +1,243 @@
+//! Blocks of a template: `{ expression }`, `{ for iterator }`,
+//! `{ if condition { .. } else { .. } }` and `{ match value { pattern => { .. } } }`.
+
+use proc_macro2::{Delimiter, Ident, TokenStream, TokenTree};
+use quote::{quote, quote_spanned, ToTokens};
+use syn::parse::{Parse, ParseStream, Result};
+use syn::spanned::Spanned;
+use syn::{braced, token, Error, Expr, Token};
+
+use crate::node::{local, HtmlRoot, ToStack};
+
+pub enum HtmlBlock {
+    /// An expression converted to a node: `{ "Text" }`, `{ self.view_item() }`.
+    Expr(TokenStream),
+    /// Nodes of an iterator wrapped into a fragment: `{ for items.iter().map(view_item) }`.
+    Iterable(Expr),
+    If(HtmlIf),
+    Match(HtmlMatch),
+}
+
+impl Parse for HtmlBlock {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        let brace = braced!(content in input);
+        if content.is_empty() {
+            return Err(Error::new(brace.span, "expected an expression in the block"));
+        }
+        if content.peek(Token![for]) {
+            content.parse::<Token![for]>()?;
+            let iterable = content.parse()?;
+            if !content.is_empty() {
+                return Err(content.error("unexpected tokens after the iterator"));
+            }
+            Ok(HtmlBlock::Iterable(iterable))
+        } else if content.peek(Token![if]) {
+            let block = HtmlBlock::If(content.parse()?);
+            if !content.is_empty() {
+                return Err(content.error("unexpected tokens after the last branch"));
+            }
+            Ok(block)
+        } else if content.peek(Token![match]) {
+            Ok(HtmlBlock::Match(content.parse()?))
+        } else {
+            Ok(HtmlBlock::Expr(content.parse()?))
+        }
+    }
+}
+
+impl ToStack for HtmlBlock {
+    fn to_stack(&self, stack: &Ident) -> TokenStream {
+        let node = match *self {
+            HtmlBlock::Expr(ref expr) => {
+                // Errors of the conversion point to the expression
+                quote_spanned! { expr.span()=> ::yew::macros::into_node({ #expr }) }
+            }
+            HtmlBlock::Iterable(ref iterable) => {
+                let (vlist, item) = (local("vlist"), local("item"));
+                let from = quote_spanned! { iterable.span()=> ::yew::macros::into_node(#item) };
+                quote! {{
+                    let mut #vlist = ::yew::virtual_dom::VList::new();
+                    for #item in #iterable {
+                        #vlist.add_child(#from);
+                    }
+                    ::yew::virtual_dom::VNode::from(#vlist)
+                }}
+            }
+            HtmlBlock::If(ref html_if) => html_if.into_token_stream(),
+            HtmlBlock::Match(ref html_match) => html_match.into_token_stream(),
+        };
+        let node_var = local("node");
+        quote! {
+            let #node_var = #node;
+            ::yew::macros::add_or_push(&mut #stack, #node_var);
+        }
+    }
+}
+
+/// A chain of conditions. The condition is taken as is till the block of the branch,
+/// so `if let` is supported too.
+pub struct HtmlIf {
+    condition: TokenStream,
+    then: Branch,
+    otherwise: Option<Box<Else>>,
+}
+
+enum Else {
+    If(HtmlIf),
+    Branch(Branch),
+}
+
+impl Parse for HtmlIf {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let if_token: Token![if] = input.parse()?;
+        let mut condition = TokenStream::new();
+        while !input.peek(token::Brace) {
+            if input.is_empty() {
+                return Err(Error::new(if_token.span, "expected a block of the branch after the condition"));
+            }
+            condition.extend(Some(input.parse::<TokenTree>()?));
+        }
+        if condition.is_empty() {
+            return Err(Error::new(if_token.span, "expected a condition"));
+        }
+        let then = input.parse()?;
+        let otherwise = if input.peek(Token![else]) {
+            input.parse::<Token![else]>()?;
+            if input.peek(Token![if]) {
+                Some(Box::new(Else::If(input.parse()?)))
+            } else {
+                Some(Box::new(Else::Branch(input.parse()?)))
+            }
+        } else {
+            None
+        };
+        Ok(HtmlIf {
+            condition,
+            then,
+            otherwise,
+        })
+    }
+}
+
+impl ToTokens for HtmlIf {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let condition = &self.condition;
+        let then = &self.then;
+        let otherwise = match self.otherwise.as_deref() {
+            Some(Else::If(ref html_if)) => html_if.into_token_stream(),
+            Some(Else::Branch(ref branch)) => branch.into_token_stream(),
+            None => Branch::Empty.into_token_stream(),
+        };
+        tokens.extend(quote! {
+            if #condition { #then } else { #otherwise }
+        });
+    }
+}
+
+/// A `match` expression which arms produce nodes.
+pub struct HtmlMatch {
+    expr: TokenStream,
+    arms: Vec<(TokenStream, Branch)>,
+}
+
+impl Parse for HtmlMatch {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let match_token: Token![match] = input.parse()?;
+        // The expression is everything till the last block which contains the arms
+        let mut expr = TokenStream::new();
+        loop {
+            let at_arms = matches!(input.cursor().group(Delimiter::Brace), Some((_, _, rest)) if rest.eof());
+            if at_arms {
+                break;
+            }
+            if input.is_empty() {
+                return Err(Error::new(match_token.span, "expected a block of arms"));
+            }
+            expr.extend(Some(input.parse::<TokenTree>()?));
+        }
+        if expr.is_empty() {
+            return Err(Error::new(match_token.span, "expected an expression to match"));
+        }
+        let content;
+        braced!(content in input);
+        let mut arms = Vec::new();
+        while !content.is_empty() {
+            let mut pattern = TokenStream::new();
+            while !content.peek(Token![=>]) {
+                if content.is_empty() {
+                    return Err(content.error("expected `=>` after the pattern"));
+                }
+                pattern.extend(Some(content.parse::<TokenTree>()?));
+            }
+            let arrow: Token![=>] = content.parse()?;
+            if pattern.is_empty() {
+                return Err(Error::new_spanned(arrow, "expected a pattern"));
+            }
+            // Like in Rust, a comma is optional after a block
+            let branch = if content.peek(token::Brace) {
+                let branch = content.parse()?;
+                if content.peek(Token![,]) {
+                    content.parse::<Token![,]>()?;
+                }
+                branch
+            } else {
+                let branch = Branch::Expr(content.parse::<Expr>()?.into_token_stream());
+                if !content.is_empty() {
+                    content.parse::<Token![,]>()?;
+                }
+                branch
+            };
+            arms.push((pattern, branch));
+        }
+        Ok(HtmlMatch { expr, arms })
+    }
+}
+
+impl ToTokens for HtmlMatch {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let expr = &self.expr;
+        let arms = self.arms.iter().map(|(pattern, branch)| quote! { #pattern => { #branch } });
+        tokens.extend(quote! {
+            match #expr { #(#arms)* }
+        });
+    }
+}
+
+/// A branch of `if` or `match`. It's a template if it starts with a tag,
+/// an empty branch is an empty fragment, otherwise it's an expression
+/// (`html!` call for example).
+enum Branch {
+    Empty,
+    Html(Box<HtmlRoot>),
+    Expr(TokenStream),
+}
+
+impl Parse for Branch {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        braced!(content in input);
+        if content.is_empty() {
+            Ok(Branch::Empty)
+        } else if content.peek(Token![<]) {
+            Ok(Branch::Html(Box::new(content.parse()?)))
+        } else {
+            Ok(Branch::Expr(content.parse()?))
+        }
+    }
+}
+
+impl ToTokens for Branch {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let node = match *self {
+            Branch::Empty => quote! {
+                ::yew::virtual_dom::VNode::from(::yew::virtual_dom::VList::new())
+            },
+            Branch::Html(ref root) => root.into_token_stream(),
+            Branch::Expr(ref expr) => quote_spanned! { expr.span()=>
+                ::yew::macros::into_node({ #expr })
+            },
+        };
+        tokens.extend(node);
+    }
+}
@@ -0,0 +1,174 @@
+//! Tags of components: `<Counter: value=1 onchange=|v| Msg::Changed(v) />`
+//! and components with children: `<Card: title="Title"><p>{ "Content" }</p></Card>`.
+
+use proc_macro2::{Ident, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream, Result};
+use syn::spanned::Spanned;
+use syn::{Error, Expr, Token, Type};
+
+use crate::node::{local, mismatched, parse_children, root, HtmlNode, ToStack};
+use crate::tag::compact;
+use crate::value::parse_value;
+
+mod kw {
+    syn::custom_keyword!(with);
+}
+
+pub struct HtmlComponent {
+    ty: Type,
+    attributes: Vec<ComponentAttribute>,
+    children: Option<Vec<HtmlNode>>,
+}
+
+impl HtmlComponent {
+    /// A component is a type followed by a colon: `<Counter: ..`.
+    pub fn peek(input: ParseStream) -> bool {
+        let fork = input.fork();
+        fork.parse::<Token![<]>().is_ok()
+            && fork.parse::<Type>().is_ok()
+            && fork.peek(Token![:])
+            && !fork.peek(Token![::])
+    }
+}
+
+impl Parse for HtmlComponent {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let lt: Token![<] = input.parse()?;
+        let ty: Type = input.parse()?;
+        input.parse::<Token![:]>()?;
+        let opened = quote! { #lt #ty };
+        let mut attributes = Vec::new();
+        loop {
+            if input.peek(Token![/]) && input.peek2(Token![>]) {
+                input.parse::<Token![/]>()?;
+                input.parse::<Token![>]>()?;
+                return Ok(HtmlComponent {
+                    ty,
+                    attributes,
+                    children: None,
+                });
+            }
+            if input.peek(Token![>]) {
+                input.parse::<Token![>]>()?;
+                break;
+            }
+            if input.is_empty() {
+                return Err(Error::new_spanned(opened, "this component isn't closed, expected `>` or `/>`"));
+            }
+            attributes.push(input.parse()?);
+        }
+        let expected = format!("</{}>", compact(&ty));
+        let (children, close) = parse_children(input, &opened, &expected)?;
+        if close.name() != Some(compact(&ty)) {
+            return Err(mismatched(&close, &opened, &expected));
+        }
+        Ok(HtmlComponent {
+            ty,
+            attributes,
+            children: Some(children),
+        })
+    }
+}
+
+impl ToStack for HtmlComponent {
+    fn to_stack(&self, stack: &Ident) -> TokenStream {
+        let ty = &self.ty;
+        let (builder, props, comp) = (local("builder"), local("props"), local("comp"));
+        let keys = self.attributes.iter().filter_map(|attribute| match *attribute {
+            ComponentAttribute::Key(ref key) => Some(quote! { #comp.set_key(&(#key)); }),
+            _ => None,
+        });
+        let children = self.children.as_ref().map(|children| {
+            // Children are nodes of the parent, their listeners are activated with the component
+            let roots = children.iter().map(|child| root(std::slice::from_ref(child)));
+            quote! {
+                ::yew::virtual_dom::vcomp::Transformer::transform(&mut #comp, ::std::vec![#(#roots),*])
+            }
+        });
+        let with = self.attributes.iter().rev().find_map(|attribute| match *attribute {
+            ComponentAttribute::With(ref props) => Some(props),
+            _ => None,
+        });
+        let values = self.attributes.iter().filter_map(|attribute| match *attribute {
+            ComponentAttribute::Prop(ref name, ref value) => Some((name, transform(&comp, value))),
+            _ => None,
+        });
+        let build = match with {
+            // A whole struct is set, the other attributes replace its fields
+            Some(with) => {
+                let fields = values.map(|(name, value)| {
+                    let field = quote_spanned! { name.span()=> #props.#name };
+                    quote! { #field = #value; }
+                });
+                let children = children.map(|children| {
+                    let field = quote_spanned! { ty.span()=> #props.children };
+                    quote! { #field = #children; }
+                });
+                quote! {
+                    #[allow(unused_mut)]
+                    let mut #props: <#ty as ::yew::html::Component>::Properties = #with;
+                    #(#fields)*
+                    #children
+                }
+            }
+            // Properties are set with the builder, which has `build` method
+            // only if all required properties were set
+            None => {
+                let setters = values.map(|(name, value)| {
+                    let setter = quote_spanned! { name.span()=> .#name };
+                    quote! { #setter(#value) }
+                });
+                let children = children.map(|children| {
+                    let setter = quote_spanned! { ty.span()=> .children };
+                    quote! { #setter(#children) }
+                });
+                let finish = quote_spanned! { ty.span()=> .build() };
+                quote! {
+                    let #props = #builder #(#setters)* #children #finish;
+                }
+            }
+        };
+        quote! {
+            #[allow(unused_variables)]
+            let (#builder, mut #comp) = ::yew::virtual_dom::VComp::lazy::<#ty>();
+            #(#keys)*
+            #build
+            #comp.set_props(#props);
+            #stack.push(#comp.into());
+            ::yew::macros::child_to_parent(&mut #stack);
+        }
+    }
+}
+
+enum ComponentAttribute {
+    /// Sets a whole struct as properties: `with props`.
+    With(Expr),
+    Key(Expr),
+    Prop(Ident, Expr),
+}
+
+impl Parse for ComponentAttribute {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.peek(kw::with) && !input.peek2(Token![=]) {
+            input.parse::<kw::with>()?;
+            return Ok(ComponentAttribute::With(parse_value(input)?));
+        }
+        let name = Ident::parse_any(input)?;
+        input.parse::<Token![=]>()?;
+        let value = parse_value(input)?;
+        if name == "key" {
+            Ok(ComponentAttribute::Key(value))
+        } else {
+            Ok(ComponentAttribute::Prop(name, value))
+        }
+    }
+}
+
+/// Converts a value of a property with `Transformer` trait to a type of the property.
+fn transform(comp: &Ident, value: &Expr) -> TokenStream {
+    quote_spanned! { value.span()=>
+        ::yew::virtual_dom::vcomp::Transformer::transform(&mut #comp, #value)
+    }
+}
@@ -0,0 +1,70 @@
+//! This crate implements `html!` macro of Yew framework as a procedural macro.
+//! Use it through `yew::html`, because the generated code refers to items of `yew` crate.
+//!
+//! The template is parsed at compile time, so errors point to the code which
+//! has to be fixed: unclosed and mismatched tags, unknown properties of components
+//! and handlers of listeners with wrong types. `#[derive(Properties)]` generates
+//! builders of properties, so a missing required property is an error too.
+
+#![recursion_limit = "128"]
+
+extern crate proc_macro;
+
+mod attribute;
+mod block;
+mod component;
+mod list;
+mod node;
+mod properties;
+mod tag;
+mod value;
+
+use proc_macro::TokenStream;
+use quote::ToTokens;
+use syn::parse_macro_input;
+
+use crate::node::HtmlRoot;
+use crate::properties::PropsDerive;
+
+/// This macro implements JSX-like templates. It builds a `VNode` from the nodes of a template,
+/// multiple root nodes are wrapped into a `VList`:
+///
+/// - tags: `<div class="item" onclick=|_| Msg::Select>{ "Text" }</div>` and `<input value=self.text />`,
+/// - components: `<Counter: value=self.value onchange=|v| Msg::Changed(v) />`, nested nodes
+///   are passed to a component with `children` property: `<Card: title="Title"><p></p></Card>`,
+/// - fragments: `<>{ "first" }{ "second" }</>` and keyed fragments `<key=item.id>...</>`,
+/// - blocks with expressions: `{ self.view_header() }`, `{ for items.iter().map(view_item) }`,
+///   `{ if cond { <p></p> } else { ... } }` and `{ match value { Some(x) => { <p>{ x }</p> }, None => {} } }`.
+///
+/// Commas between attributes are optional. A value of an attribute ends before the next
+/// attribute, a comma, `>` or `/>`, so comparisons have to be wrapped with braces or parentheses:
+/// `<button disabled={ self.count > 10 }>`.
+#[proc_macro]
+pub fn html(input: TokenStream) -> TokenStream {
+    let root = parse_macro_input!(input as HtmlRoot);
+    root.into_token_stream().into()
+}
+
+/// Implements `Properties` trait for a struct with named fields. Properties are set
+/// through a generated builder, fields marked with `#[props(required)]` have to be set
+/// in a template, other fields start from a `#[props(default = ..)]` value or
+/// `Default::default()`:
+///
+/// ```ignore
+/// #[derive(Properties, Clone, PartialEq)]
+/// pub struct Props {
+///     #[props(required)]
+///     pub title: String,
+///     #[props(default = "Send".into())]
+///     pub label: String,
+///     pub onclick: Option<Callback<()>>,
+/// }
+/// ```
+///
+/// The values are used by the builder only, `Default` of the struct (which is needed
+/// to mount a root component with `App::mount`) is implemented separately.
+#[proc_macro_derive(Properties, attributes(props))]
+pub fn derive_props(input: TokenStream) -> TokenStream {
+    let props = parse_macro_input!(input as PropsDerive);
+    props.expand().into()
+}
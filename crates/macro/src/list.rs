@@ -0,0 +1,61 @@
+//! Fragments: `<>..</>` and keyed fragments `<key=item.id>..</>`.
+
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use syn::parse::{Parse, ParseStream, Result};
+use syn::{Expr, Token};
+
+use crate::node::{mismatched, parse_children, HtmlNode, ToStack};
+use crate::value::parse_value;
+
+mod kw {
+    syn::custom_keyword!(key);
+}
+
+pub struct HtmlList {
+    key: Option<Expr>,
+    children: Vec<HtmlNode>,
+}
+
+impl HtmlList {
+    pub fn peek(input: ParseStream) -> bool {
+        input.peek(Token![<])
+            && (input.peek2(Token![>]) || (input.peek2(kw::key) && input.peek3(Token![=])))
+    }
+}
+
+impl Parse for HtmlList {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let lt: Token![<] = input.parse()?;
+        let key = if input.peek(kw::key) {
+            input.parse::<kw::key>()?;
+            input.parse::<Token![=]>()?;
+            Some(parse_value(input)?)
+        } else {
+            None
+        };
+        let gt: Token![>] = input.parse()?;
+        let opened = quote! { #lt #gt };
+        let (children, close) = parse_children(input, &opened, "</>")?;
+        if close.name().is_some() {
+            return Err(mismatched(&close, &opened, "</>"));
+        }
+        Ok(HtmlList { key, children })
+    }
+}
+
+impl ToStack for HtmlList {
+    fn to_stack(&self, stack: &Ident) -> TokenStream {
+        let vlist = crate::node::local("vlist");
+        let key = self.key.as_ref().map(|key| quote! { #vlist.set_key(&(#key)); });
+        let children = self.children.iter().map(|child| child.to_stack(stack));
+        quote! {
+            #[allow(unused_mut)]
+            let mut #vlist = ::yew::virtual_dom::VList::new();
+            #key
+            #stack.push(#vlist.into());
+            #(#children)*
+            ::yew::macros::child_to_parent(&mut #stack);
+        }
+    }
+}
@@ -0,0 +1,141 @@
+//! The root of a template and nodes of it.
+
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::{quote, ToTokens};
+use syn::parse::{Parse, ParseStream, Result};
+use syn::{token, Error, Token};
+
+use crate::block::HtmlBlock;
+use crate::component::HtmlComponent;
+use crate::list::HtmlList;
+use crate::tag::{HtmlTag, TagClose};
+
+/// A part of a template which generates statements putting its nodes to the stack.
+/// The stack works like in the former `html_impl!` macro: an opened tag is pushed
+/// to the stack and it's moved to the children of the parent when it's closed.
+pub trait ToStack {
+    fn to_stack(&self, stack: &Ident) -> TokenStream;
+}
+
+/// Creates an identifier of a local variable of the generated code.
+/// It has `mixed_site` hygiene, so expressions of a template can't clash with it.
+pub fn local(name: &str) -> Ident {
+    Ident::new(name, Span::mixed_site())
+}
+
+/// A template. Multiple root nodes are wrapped into a fragment.
+pub struct HtmlRoot {
+    nodes: Vec<HtmlNode>,
+}
+
+impl Parse for HtmlRoot {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut nodes = Vec::new();
+        while !input.is_empty() {
+            nodes.push(input.parse()?);
+        }
+        Ok(HtmlRoot { nodes })
+    }
+}
+
+impl ToTokens for HtmlRoot {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        tokens.extend(root(&self.nodes));
+    }
+}
+
+/// Generates an expression which builds a `VNode` from the nodes of a template.
+pub fn root(nodes: &[HtmlNode]) -> TokenStream {
+    match nodes.len() {
+        0 => quote! {
+            ::yew::virtual_dom::VNode::from(::yew::virtual_dom::VList::new())
+        },
+        len => {
+            let (stack, value) = (local("__yew_stack"), local("__yew_root"));
+            let statements = nodes.iter().map(|node| node.to_stack(&stack));
+            let fragment = if len > 1 {
+                let vlist = local("vlist");
+                Some(quote! {
+                    let #vlist = ::yew::virtual_dom::VList::new();
+                    #stack.push(#vlist.into());
+                })
+            } else {
+                None
+            };
+            // Values are often wrapped with braces in templates: `value={ &self.value }`
+            quote! {{
+                #[allow(unused_braces)]
+                let #value = {
+                    let mut #stack = ::std::vec::Vec::new();
+                    #fragment
+                    #(#statements)*
+                    ::yew::macros::unpack(#stack)
+                };
+                #value
+            }}
+        }
+    }
+}
+
+/// A node of a template.
+pub enum HtmlNode {
+    Tag(HtmlTag),
+    Component(HtmlComponent),
+    List(HtmlList),
+    Block(HtmlBlock),
+}
+
+impl Parse for HtmlNode {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.peek(token::Brace) {
+            Ok(HtmlNode::Block(input.parse()?))
+        } else if TagClose::peek(input) {
+            let close: TagClose = input.parse()?;
+            Err(Error::new_spanned(close, "this closing tag has no opening tag"))
+        } else if HtmlList::peek(input) {
+            Ok(HtmlNode::List(input.parse()?))
+        } else if HtmlComponent::peek(input) {
+            Ok(HtmlNode::Component(input.parse()?))
+        } else if input.peek(Token![<]) {
+            Ok(HtmlNode::Tag(input.parse()?))
+        } else {
+            Err(input.error("you should use curly braces for text nodes: <a>{ \"Link\" }</a>"))
+        }
+    }
+}
+
+impl ToStack for HtmlNode {
+    fn to_stack(&self, stack: &Ident) -> TokenStream {
+        match *self {
+            HtmlNode::Tag(ref tag) => tag.to_stack(stack),
+            HtmlNode::Component(ref comp) => comp.to_stack(stack),
+            HtmlNode::List(ref list) => list.to_stack(stack),
+            HtmlNode::Block(ref block) => block.to_stack(stack),
+        }
+    }
+}
+
+/// Parses children of a tag or a fragment till the closing tag.
+/// The closing tag is parsed too and returned to be checked by the caller.
+pub fn parse_children(input: ParseStream, opened: &dyn ToTokens, expected: &str) -> Result<(Vec<HtmlNode>, TagClose)> {
+    let mut children = Vec::new();
+    loop {
+        if input.is_empty() {
+            let message = format!("this tag isn't closed, expected `{}`", expected);
+            return Err(Error::new_spanned(opened, message));
+        }
+        if TagClose::peek(input) {
+            let close = input.parse()?;
+            return Ok((children, close));
+        }
+        children.push(input.parse()?);
+    }
+}
+
+/// Creates an error of a closing tag which doesn't match the opening tag.
+pub fn mismatched(close: &TagClose, opened: &dyn ToTokens, expected: &str) -> Error {
+    let message = format!("mismatched closing tag, expected `{}`", expected);
+    let mut error = Error::new_spanned(close, message);
+    error.combine(Error::new_spanned(opened, "the tag is opened here"));
+    error
+}
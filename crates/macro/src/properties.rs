@@ -0,0 +1,253 @@
+//! `#[derive(Properties)]` generates a builder of properties which is used by `html!`.
+//!
+//! Every required field has its own type parameter of the builder. It's a marker type
+//! till the field is set and `()` after that, so `build` is available only when
+//! all required fields were set and a missing property is a compile-time error.
+//! Other fields start from `#[props(default = ..)]` values or `Default::default()`.
+
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::parse::{Parse, ParseStream, Result};
+use syn::{parse_quote, Data, DeriveInput, Error, Expr, Fields, GenericParam, Token, Type, Visibility};
+
+mod kw {
+    syn::custom_keyword!(required);
+    syn::custom_keyword!(default);
+}
+
+pub struct PropsDerive {
+    vis: Visibility,
+    name: Ident,
+    generics: syn::Generics,
+    fields: Vec<PropField>,
+}
+
+struct PropField {
+    name: Ident,
+    ty: Type,
+    required: bool,
+    default: Option<Expr>,
+}
+
+/// An argument of `#[props(..)]` attribute.
+enum PropAttr {
+    Required,
+    Default(Box<Expr>),
+}
+
+impl Parse for PropAttr {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.peek(kw::required) {
+            input.parse::<kw::required>()?;
+            Ok(PropAttr::Required)
+        } else {
+            input.parse::<kw::default>()?;
+            input.parse::<Token![=]>()?;
+            Ok(PropAttr::Default(Box::new(input.parse()?)))
+        }
+    }
+}
+
+impl Parse for PropsDerive {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let input: DeriveInput = input.parse()?;
+        let named = match input.data {
+            Data::Struct(ref data) => match data.fields {
+                Fields::Named(ref named) => named,
+                _ => return Err(Error::new_spanned(&input.ident, "properties have to be a struct with named fields")),
+            },
+            _ => return Err(Error::new_spanned(&input.ident, "properties have to be a struct with named fields")),
+        };
+        let mut fields = Vec::new();
+        for field in &named.named {
+            let mut required = false;
+            let mut default = None;
+            for attr in field.attrs.iter().filter(|attr| attr.path.is_ident("props")) {
+                match attr.parse_args::<PropAttr>()? {
+                    PropAttr::Required => required = true,
+                    PropAttr::Default(value) => default = Some(*value),
+                }
+                if required && default.is_some() {
+                    return Err(Error::new_spanned(attr, "a required property can't have a default value"));
+                }
+            }
+            fields.push(PropField {
+                name: field.ident.clone().expect("named field expected"),
+                ty: field.ty.clone(),
+                required,
+                default,
+            });
+        }
+        Ok(PropsDerive {
+            vis: input.vis,
+            name: input.ident,
+            generics: input.generics,
+            fields,
+        })
+    }
+}
+
+impl PropsDerive {
+    pub fn expand(&self) -> TokenStream {
+        let PropsDerive { ref vis, ref name, .. } = *self;
+        // Defaults of parameters aren't allowed in impls and before the state parameters
+        let mut generics = self.generics.clone();
+        for param in generics.type_params_mut() {
+            param.eq_token = None;
+            param.default = None;
+        }
+        // Derived `Clone` and `PartialEq` of generic properties require bounds of parameters
+        let props_where = {
+            let mut generics = generics.clone();
+            let (_, ty_generics, _) = self.generics.split_for_impl();
+            generics
+                .make_where_clause()
+                .predicates
+                .push(parse_quote! { #name #ty_generics: ::std::clone::Clone + ::std::cmp::PartialEq });
+            generics.where_clause
+        };
+        let builder = format_ident!("{}Builder", name);
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+        let params: Vec<_> = generics.params.iter().collect();
+        let args: Vec<TokenStream> = generics
+            .params
+            .iter()
+            .map(|param| match *param {
+                GenericParam::Type(ref param) => {
+                    let ident = &param.ident;
+                    quote! { #ident }
+                }
+                GenericParam::Lifetime(ref param) => {
+                    let lifetime = &param.lifetime;
+                    quote! { #lifetime }
+                }
+                GenericParam::Const(ref param) => {
+                    let ident = &param.ident;
+                    quote! { #ident }
+                }
+            })
+            .collect();
+        let types: Vec<&Ident> = generics.type_params().map(|param| &param.ident).collect();
+
+        let required: Vec<&PropField> = self.fields.iter().filter(|field| field.required).collect();
+        let optional: Vec<&PropField> = self.fields.iter().filter(|field| !field.required).collect();
+        // A state parameter and a marker of the unset state for every required field
+        let states: Vec<Ident> = required
+            .iter()
+            .enumerate()
+            .map(|(index, _)| Ident::new(&format!("__YewState{}", index), Span::call_site()))
+            .collect();
+        let markers: Vec<Ident> = required
+            .iter()
+            .map(|field| format_ident!("{}_missing_{}", builder, field.name))
+            .collect();
+
+        let names: Vec<&Ident> = self.fields.iter().map(|field| &field.name).collect();
+        let storage = self.fields.iter().map(|field| {
+            let (name, ty) = (&field.name, &field.ty);
+            if field.required {
+                quote! { #name: ::std::option::Option<#ty> }
+            } else {
+                quote! { #name: #ty }
+            }
+        });
+        let initial = self.fields.iter().map(|field| {
+            let name = &field.name;
+            match (field.required, &field.default) {
+                (true, _) => quote! { #name: ::std::option::Option::None },
+                (false, Some(value)) => quote! { #name: #value },
+                (false, None) => quote! { #name: ::std::default::Default::default() },
+            }
+        });
+        let unwrap = self.fields.iter().map(|field| {
+            let name = &field.name;
+            if field.required {
+                quote! { #name: self.#name.unwrap() }
+            } else {
+                quote! { #name: self.#name }
+            }
+        });
+
+        let required_setters = required.iter().enumerate().map(|(index, field)| {
+            let (name, ty) = (&field.name, &field.ty);
+            let before = &states;
+            let after = states.iter().enumerate().map(|(other, state)| {
+                if other == index {
+                    quote! { () }
+                } else {
+                    quote! { #state }
+                }
+            });
+            let moved = names.iter().filter(|other| **other != name);
+            quote! {
+                impl<#(#params,)* #(#before),*> #builder<#(#args,)* #(#before),*> #where_clause {
+                    #[doc(hidden)]
+                    #vis fn #name(self, #name: #ty) -> #builder<#(#args,)* #(#after),*> {
+                        #builder {
+                            #name: ::std::option::Option::Some(#name),
+                            #(#moved: self.#moved,)*
+                            _state: ::std::marker::PhantomData,
+                        }
+                    }
+                }
+            }
+        });
+        let optional_setters = optional.iter().map(|field| {
+            let (name, ty) = (&field.name, &field.ty);
+            quote! {
+                #[doc(hidden)]
+                #vis fn #name(mut self, #name: #ty) -> Self {
+                    self.#name = #name;
+                    self
+                }
+            }
+        });
+        let unset = states.iter().map(|_| quote! { () });
+
+        quote! {
+            #(
+                #[doc(hidden)]
+                #[allow(non_camel_case_types)]
+                #vis struct #markers;
+            )*
+
+            #[doc(hidden)]
+            #vis struct #builder<#(#params,)* #(#states),*> #where_clause {
+                #(#storage,)*
+                _state: ::std::marker::PhantomData<(#(#types,)* #(#states,)*)>,
+            }
+
+            impl #impl_generics ::yew::html::Properties for #name #ty_generics #props_where {
+                type Builder = #builder<#(#args,)* #(#markers),*>;
+
+                fn builder() -> Self::Builder {
+                    #builder {
+                        #(#initial,)*
+                        _state: ::std::marker::PhantomData,
+                    }
+                }
+
+                fn fmt_debug(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    #[allow(unused_imports)]
+                    use ::yew::html::{WithDebug, WithoutDebug};
+                    (&::yew::html::DebugProps(self)).fmt_props(f)
+                }
+            }
+
+            impl<#(#params,)* #(#states),*> #builder<#(#args,)* #(#states),*> #where_clause {
+                #(#optional_setters)*
+            }
+
+            #(#required_setters)*
+
+            impl #impl_generics #builder<#(#args,)* #(#unset),*> #where_clause {
+                #[doc(hidden)]
+                #vis fn build(self) -> #name #ty_generics {
+                    #name {
+                        #(#unwrap,)*
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,178 @@
+//! Tags of elements.
+
+use std::fmt;
+
+use proc_macro2::{Ident, TokenStream};
+use quote::{quote, ToTokens};
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream, Result};
+use syn::punctuated::Punctuated;
+use syn::{Error, Token, Type};
+
+use crate::attribute::TagAttribute;
+use crate::node::{mismatched, parse_children, HtmlNode, ToStack};
+
+/// A name of a tag or an attribute which could contain hyphens: `my-slider`, `aria-label`.
+/// Keywords are allowed, because `type`, `for` or `loop` are valid names.
+pub struct TagName {
+    parts: Punctuated<Ident, Token![-]>,
+}
+
+impl TagName {
+    /// Returns the name if it doesn't contain hyphens.
+    pub fn single(&self) -> Option<&Ident> {
+        if self.parts.len() == 1 {
+            self.parts.first()
+        } else {
+            None
+        }
+    }
+}
+
+impl Parse for TagName {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut parts = Punctuated::new();
+        parts.push_value(Ident::parse_any(input)?);
+        while input.peek(Token![-]) {
+            parts.push_punct(input.parse()?);
+            parts.push_value(Ident::parse_any(input)?);
+        }
+        Ok(TagName { parts })
+    }
+}
+
+impl ToTokens for TagName {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.parts.to_tokens(tokens);
+    }
+}
+
+impl fmt::Display for TagName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts = self.parts.iter().map(Ident::to_string).collect::<Vec<_>>();
+        f.write_str(&parts.join("-"))
+    }
+}
+
+/// A closing tag: `</div>`, `</Card>` of a component or `</>` of a fragment.
+pub struct TagClose {
+    lt: Token![<],
+    slash: Token![/],
+    name: Option<TokenStream>,
+    gt: Token![>],
+}
+
+impl TagClose {
+    pub fn peek(input: ParseStream) -> bool {
+        input.peek(Token![<]) && input.peek2(Token![/])
+    }
+
+    /// Returns the name of the tag without whitespaces to be compared
+    /// with the name of the opening tag.
+    pub fn name(&self) -> Option<String> {
+        self.name.as_ref().map(|name| compact(name))
+    }
+}
+
+/// Removes whitespaces from tokens of a name: `Select < Scene >` becomes `Select<Scene>`.
+pub fn compact(tokens: &dyn ToTokens) -> String {
+    let text = tokens.to_token_stream().to_string();
+    text.chars().filter(|c| !c.is_whitespace()).collect()
+}
+
+impl Parse for TagClose {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let lt = input.parse()?;
+        let slash = input.parse()?;
+        let name = if input.peek(Token![>]) {
+            None
+        } else {
+            // A name of a tag (`my-slider`) or a type of a component (`Select<Scene>`)
+            let fork = input.fork();
+            if fork.parse::<TagName>().is_ok() && fork.peek(Token![>]) {
+                Some(input.parse::<TagName>()?.into_token_stream())
+            } else {
+                Some(input.parse::<Type>()?.into_token_stream())
+            }
+        };
+        let gt = input.parse()?;
+        Ok(TagClose { lt, slash, name, gt })
+    }
+}
+
+impl ToTokens for TagClose {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.lt.to_tokens(tokens);
+        self.slash.to_tokens(tokens);
+        self.name.to_tokens(tokens);
+        self.gt.to_tokens(tokens);
+    }
+}
+
+/// A tag of an element: `<div class="item">{ "Text" }</div>` or `<input value="" />`.
+pub struct HtmlTag {
+    name: TagName,
+    attributes: Vec<TagAttribute>,
+    children: Vec<HtmlNode>,
+}
+
+impl Parse for HtmlTag {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let lt: Token![<] = input.parse()?;
+        let name: TagName = input.parse()?;
+        let opened = quote! { #lt #name };
+        let mut attributes = Vec::new();
+        loop {
+            if input.peek(Token![/]) && input.peek2(Token![>]) {
+                input.parse::<Token![/]>()?;
+                input.parse::<Token![>]>()?;
+                return Ok(HtmlTag {
+                    name,
+                    attributes,
+                    children: Vec::new(),
+                });
+            }
+            if input.peek(Token![>]) {
+                input.parse::<Token![>]>()?;
+                break;
+            }
+            if input.is_empty() {
+                return Err(Error::new_spanned(opened, "this tag isn't closed, expected `>` or `/>`"));
+            }
+            attributes.push(input.parse()?);
+        }
+        let expected = format!("</{}>", name);
+        let (children, close) = parse_children(input, &opened, &expected)?;
+        let matched = matches!(
+            close.name(),
+            Some(close_name) if close_name.eq_ignore_ascii_case(&name.to_string())
+        );
+        if !matched {
+            return Err(mismatched(&close, &opened, &expected));
+        }
+        Ok(HtmlTag {
+            name,
+            attributes,
+            children,
+        })
+    }
+}
+
+impl ToStack for HtmlTag {
+    fn to_stack(&self, stack: &Ident) -> TokenStream {
+        let name = self.name.to_string();
+        let ns = if name == "svg" {
+            quote! { ::std::option::Option::Some("http://www.w3.org/2000/svg".into()) }
+        } else {
+            quote! { ::std::option::Option::None }
+        };
+        let attributes = self.attributes.iter().map(|attribute| attribute.to_stack(stack));
+        let children = self.children.iter().map(|child| child.to_stack(stack));
+        quote! {
+            #stack.push(::yew::virtual_dom::VTag::new(#name, #ns).into());
+            #(#attributes)*
+            #(#children)*
+            ::yew::macros::child_to_parent(&mut #stack);
+        }
+    }
+}
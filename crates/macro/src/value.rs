@@ -0,0 +1,114 @@
+//! Values of attributes. A value is an expression without a delimiter after it,
+//! so it's collected token by token till something which can't be a part of it.
+
+use proc_macro2::{Delimiter, Spacing, TokenStream, TokenTree};
+use syn::buffer::Cursor;
+use syn::parse::{ParseStream, Result};
+use syn::{Error, Expr, Token};
+
+/// Parses a value of an attribute. The value ends before a comma (which is consumed),
+/// `>` or `/>` of the tag or the beginning of the next attribute: `name=`, `data-id=`,
+/// `on:event=`, `onclick.prevent=` or `(onclick)=`. Groups (`(..)`, `[..]` and `{..}`)
+/// are taken as a whole, so comparisons and shifts could be used in them.
+pub fn parse_value(input: ParseStream) -> Result<Expr> {
+    let start = input.span();
+    let mut tokens = TokenStream::new();
+    let mut prev: Option<TokenTree> = None;
+    // Depth of generic arguments of a turbofish: `iter.collect::<Vec<_>>()`
+    let mut generics = 0usize;
+    loop {
+        let cursor = input.cursor();
+        if cursor.eof() {
+            break;
+        }
+        if generics == 0 {
+            if let Some((punct, rest)) = cursor.punct() {
+                match punct.as_char() {
+                    ',' => {
+                        input.parse::<Token![,]>()?;
+                        break;
+                    }
+                    '>' if ends_tag(&prev, punct.spacing(), rest) => break,
+                    '/' if punct.spacing() == Spacing::Joint && is_punct(rest, '>') => break,
+                    _ => {}
+                }
+            } else if matches!(prev, Some(ref prev) if !is_punct_tree(prev)) && starts_attribute(cursor) {
+                break;
+            }
+        }
+        let tree: TokenTree = input.parse()?;
+        if let TokenTree::Punct(ref punct) = tree {
+            match punct.as_char() {
+                '<' if generics > 0 || matches!(prev, Some(ref prev) if is_char(prev, ':')) => generics += 1,
+                '>' if generics > 0 => generics -= 1,
+                _ => {}
+            }
+        }
+        tokens.extend(Some(tree.clone()));
+        prev = Some(tree);
+    }
+    if tokens.is_empty() {
+        return Err(Error::new(start, "expected a value of the attribute"));
+    }
+    syn::parse2(tokens)
+}
+
+/// Checks that `>` closes a tag and it's not a part of `->`, `=>`, `>=` or `>>`.
+fn ends_tag(prev: &Option<TokenTree>, spacing: Spacing, rest: Cursor) -> bool {
+    if let Some(TokenTree::Punct(ref prev)) = *prev {
+        if prev.spacing() == Spacing::Joint {
+            return false;
+        }
+    }
+    // `>` is joint before any punctuation, `><` is a valid end of the tag
+    !(spacing == Spacing::Joint && (is_punct(rest, '=') || is_punct(rest, '>')))
+}
+
+/// Checks that the cursor points to a beginning of an attribute:
+/// a name (possibly with hyphens, a prefix or modifiers) followed by `=`
+/// or a name of a listener in parentheses: `(onclick)=`.
+fn starts_attribute(cursor: Cursor) -> bool {
+    if let Some((inside, _, rest)) = cursor.group(Delimiter::Parenthesis) {
+        let single_ident = matches!(inside.ident(), Some((_, after)) if after.eof());
+        return single_ident && is_assign(rest);
+    }
+    let mut rest = match cursor.ident() {
+        Some((_, rest)) => rest,
+        None => return false,
+    };
+    while let Some((punct, next)) = rest.punct() {
+        match punct.as_char() {
+            '=' => return is_assign(rest),
+            ':' if punct.spacing() == Spacing::Joint => return false,
+            '-' | '.' | ':' => match next.ident() {
+                Some((_, after)) => rest = after,
+                None => return false,
+            },
+            _ => return false,
+        }
+    }
+    false
+}
+
+fn is_punct(cursor: Cursor, ch: char) -> bool {
+    matches!(cursor.punct(), Some((punct, _)) if punct.as_char() == ch)
+}
+
+/// Checks that the cursor points to `=` which isn't a part of `==` or `=>`.
+fn is_assign(cursor: Cursor) -> bool {
+    match cursor.punct() {
+        Some((punct, next)) if punct.as_char() == '=' => !(is_punct(next, '=') || is_punct(next, '>')),
+        _ => false,
+    }
+}
+
+fn is_punct_tree(tree: &TokenTree) -> bool {
+    matches!(*tree, TokenTree::Punct(_))
+}
+
+fn is_char(tree: &TokenTree, ch: char) -> bool {
+    match *tree {
+        TokenTree::Punct(ref punct) => punct.as_char() == ch,
+        _ => false,
+    }
+}
@@ -4,7 +4,7 @@
 
 use pulldown_cmark::{Alignment, Event, Parser, Tag, OPTION_ENABLE_TABLES};
 use yew::{html, Component, Html};
-use yew::virtual_dom::{VNode, VTag, VText};
+use yew::virtual_dom::{VNode, VRaw, VTag, VText};
 
 /// Renders a string of Markdown to HTML with the default options (footnotes
 /// disabled, tables enabled).
@@ -70,6 +70,16 @@ where
             Event::Text(text) => add_child!(VText::new(text.to_string()).into()),
             Event::SoftBreak => add_child!(VText::new("\n".to_string()).into()),
             Event::HardBreak => add_child!(VTag::new("br").into()),
+            Event::Html(html) | Event::InlineHtml(html) => {
+                let raw = VRaw::new(html.to_string()).sanitize(sanitize);
+                if spine.is_empty() {
+                    let mut block = VTag::new("div");
+                    block.add_child(raw.into());
+                    elems.push(block);
+                } else {
+                    add_child!(raw.into());
+                }
+            }
             _ => println!("Unknown event: {:#?}", ev),
         }
     }
@@ -83,6 +93,32 @@ where
     }
 }
 
+/// Keeps simple formatting tags of a raw HTML and escapes the rest of it,
+/// because descriptions are typed by users.
+fn sanitize(html: &str) -> String {
+    const ALLOWED: &[&str] = &[
+        "<b>", "</b>", "<i>", "</i>", "<em>", "</em>", "<strong>", "</strong>", "<br>",
+    ];
+    let mut out = String::new();
+    let mut rest = html;
+    while let Some(c) = rest.chars().next() {
+        if let Some(tag) = ALLOWED.iter().find(|tag| rest.starts_with(*tag)) {
+            out.push_str(tag);
+            rest = &rest[tag.len()..];
+            continue;
+        }
+        match c {
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '&' => out.push_str("&amp;"),
+            '"' => out.push_str("&quot;"),
+            c => out.push(c),
+        }
+        rest = &rest[c.len_utf8()..];
+    }
+    out
+}
+
 fn make_tag<COMP>(t: Tag) -> VTag<COMP>
 where
     COMP: Component,
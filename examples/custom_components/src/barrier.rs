@@ -1,5 +1,5 @@
 use crate::button::Button;
-use yew::{html, Callback, Component, ComponentLink, Html, Renderable, ShouldRender};
+use yew::{html, Callback, Component, ComponentLink, Html, Properties, Renderable, ShouldRender};
 
 pub struct Barrier {
     limit: u32,
@@ -11,22 +11,13 @@ pub enum Msg {
     ChildClicked,
 }
 
-#[derive(PartialEq, Clone)]
+#[derive(Properties, PartialEq, Clone)]
 pub struct Props {
+    #[props(required)]
     pub limit: u32,
     pub onsignal: Option<Callback<()>>,
 }
 
-impl Default for Props {
-    fn default() -> Self {
-        Props {
-            limit: 0,
-            onsignal: None,
-        }
-    }
-}
-
-
 impl Component for Barrier {
     type Message = Msg;
     type Properties = Props;
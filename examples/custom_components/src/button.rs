@@ -1,4 +1,4 @@
-use yew::{html, Callback, Component, ComponentLink, Html, Renderable, ShouldRender};
+use yew::{html, Callback, Component, ComponentLink, Html, Properties, Renderable, ShouldRender};
 
 pub struct Button {
     title: String,
@@ -9,21 +9,13 @@ pub enum Msg {
     Clicked,
 }
 
-#[derive(PartialEq, Clone)]
+#[derive(Properties, PartialEq, Clone)]
 pub struct Props {
+    #[props(default = "Send Signal".into())]
     pub title: String,
     pub onsignal: Option<Callback<()>>,
 }
 
-impl Default for Props {
-    fn default() -> Self {
-        Props {
-            title: "Send Signal".into(),
-            onsignal: None,
-        }
-    }
-}
-
 impl Component for Button {
     type Message = Msg;
     type Properties = Props;
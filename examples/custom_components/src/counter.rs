@@ -1,4 +1,4 @@
-use yew::{html, Callback, Component, ComponentLink, Html, Renderable, ShouldRender};
+use yew::{html, Callback, Component, ComponentLink, Html, Properties, Renderable, ShouldRender};
 
 #[derive(PartialEq, Clone)]
 pub enum Color {
@@ -17,23 +17,14 @@ pub enum Msg {
     Increase,
 }
 
-#[derive(PartialEq, Clone)]
+#[derive(Properties, PartialEq, Clone)]
 pub struct Props {
     pub initial: u32,
+    #[props(required)]
     pub color: Color,
     pub onclick: Option<Callback<u32>>,
 }
 
-impl Default for Props {
-    fn default() -> Self {
-        Props {
-            initial: 0,
-            color: Color::Green,
-            onclick: None,
-        }
-    }
-}
-
 impl Component for Counter {
     type Message = Msg;
     type Properties = Props;
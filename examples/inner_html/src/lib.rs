@@ -1,11 +1,7 @@
 #![recursion_limit="512"]
-#[macro_use]
-extern crate stdweb;
 
-use stdweb::web::Node;
-use stdweb::unstable::TryFrom;
-use yew::{Component, ComponentLink, Html, Renderable, ShouldRender};
-use yew::virtual_dom::VNode;
+use yew::{html, Component, ComponentLink, Html, Renderable, ShouldRender};
+use yew::virtual_dom::VRaw;
 
 pub struct Model {
     pub value: i64,
@@ -40,16 +36,9 @@ const SVG: &str = r#"
 
 impl Renderable<Model> for Model {
     fn view(&self) -> Html<Self> {
-        let js_svg = js! {
-            var div = document.createElement("div");
-            div.innerHTML = @{SVG.to_string()};
-            console.log(div);
-            return div;
-        };
-        eprintln!("js_svg: {:?}", js_svg);
-        let node = Node::try_from(js_svg).expect("convert js_svg");
-        let vnode = VNode::VRef(node);
-        eprintln!("svg: {:?}", vnode);
-        vnode
+        // The HTML is parsed once, the next renders keep the nodes
+        html! {
+            <div>{ VRaw::new(SVG) }</div>
+        }
     }
 }
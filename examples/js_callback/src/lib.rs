@@ -3,7 +3,7 @@
 #[macro_use]
 extern crate stdweb;
 
-use yew::{html, Callback, Component, ComponentLink, Html, Renderable, ShouldRender};
+use yew::{html, Callback, Component, ComponentLink, Html, Properties, Renderable, ShouldRender};
 
 pub struct Model {
     payload: String,
@@ -17,7 +17,7 @@ pub enum Msg {
     AsyncPayload,
 }
 
-#[derive(Default, PartialEq, Eq, Clone)]
+#[derive(Properties, Default, PartialEq, Eq, Clone)]
 pub struct Props {
     payload: String,
 }
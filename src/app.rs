@@ -3,11 +3,14 @@
 
 use stdweb::web::{document, Element, INode, IParentNode};
 use html::{Scope, Component, Renderable};
+use virtual_dom::Delegation;
 
 /// An application instance.
 pub struct App<COMP: Component> {
     /// `Scope` holder
     scope: Scope<COMP>,
+    /// Delegate listeners to the mount element
+    delegate_events: bool,
 }
 
 impl<COMP> App<COMP>
@@ -17,26 +20,90 @@ where
     /// Creates a new `App` with a component in a context.
     pub fn new() -> Self {
         let scope = Scope::new();
-        App { scope }
+        App {
+            scope,
+            delegate_events: false,
+        }
     }
 
-    /// Alias to `mount("body", ...)`.
-    pub fn mount_to_body(self) -> Scope<COMP> {
+    /// Turns on delegation of events. Instead of a JS listener for every element
+    /// the app installs a single listener per event type on the mount element
+    /// and dispatches events to listeners of the nearest element on the way
+    /// from `event.target`. It saves memory and mount time of large trees.
+    /// Listeners of custom events (`on:` prefix) are still added to elements.
+    pub fn with_event_delegation(mut self) -> Self {
+        self.delegate_events = true;
+        self
+    }
+
+    /// Alias to `mount("body", ...)`. Properties of the component are created
+    /// with `Default`, so it's not available for properties with required fields.
+    pub fn mount_to_body(self) -> Scope<COMP>
+    where
+        COMP::Properties: Default,
+    {
+        self.mount_to_body_with_props(Default::default())
+    }
+
+    /// Alias to `mount_with_props("body", ...)`.
+    pub fn mount_to_body_with_props(self, props: COMP::Properties) -> Scope<COMP> {
         // Bootstrap the component for `Window` environment only (not for `Worker`)
         let element = document()
             .query_selector("body")
             .expect("can't get body node for rendering")
             .expect("can't unwrap body node");
-        self.mount(element)
+        self.mount_with_props(element, props)
     }
 
     /// The main entrypoint of a yew program. It works similar as `program`
     /// function in Elm. You should provide an initial model, `update` function
     /// which will update the state of the model and a `view` function which
     /// will render the model to a virtual DOM tree.
-    pub fn mount(self, element: Element) -> Scope<COMP> {
+    ///
+    /// The root component has no parent which sets its properties, so they are
+    /// created with `Default`. Use `mount_with_props` for required properties.
+    pub fn mount(self, element: Element) -> Scope<COMP>
+    where
+        COMP::Properties: Default,
+    {
+        self.mount_with_props(element, Default::default())
+    }
+
+    /// Mounts the component with the given properties. Use it for
+    /// a root component with required properties.
+    pub fn mount_with_props(self, element: Element, props: COMP::Properties) -> Scope<COMP> {
         clear_element(&element);
-        self.scope.mount_in_place(element, None, None, None)
+        self.scope_for(&element).mount_in_place(element, None, None, props)
+    }
+
+    /// Mounts the component over the children of the element which were rendered
+    /// before (on a server with `yew::ssr`). Existing DOM nodes are bound to the virtual
+    /// nodes and listeners are attached to them instead of recreating the whole tree.
+    /// Mismatched nodes are replaced and reported (in debug builds only).
+    /// Properties are created with `Default` like `mount` does.
+    pub fn hydrate(self, element: Element) -> Scope<COMP>
+    where
+        COMP::Properties: Default,
+    {
+        self.hydrate_with_props(element, Default::default())
+    }
+
+    /// Hydrates the component with the given properties. They have to be
+    /// the same as the properties which were used to render the nodes.
+    pub fn hydrate_with_props(self, element: Element, props: COMP::Properties) -> Scope<COMP> {
+        let cursor = element.first_child();
+        self.scope_for(&element).hydrate_in_place(element, cursor, None, props)
+    }
+
+    fn scope_for(self, element: &Element) -> Scope<COMP> {
+        let delegation = {
+            if self.delegate_events {
+                Some(Delegation::new(element.clone()))
+            } else {
+                None
+            }
+        };
+        self.scope.with_delegation(delegation)
     }
 }
 
@@ -30,7 +30,7 @@ pub enum Msg {
 }
 
 /// Properties of `Select` component.
-#[derive(PartialEq, Clone)]
+#[derive(Properties, PartialEq, Clone)]
 pub struct Props<T> {
     /// Initially selected value.
     pub selected: Option<T>,
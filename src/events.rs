@@ -0,0 +1,279 @@
+//! The module that contains all events available in the framework.
+//!
+//! Most of events are provided by `stdweb`. Events which `stdweb` doesn't
+//! have are declared here.
+
+use serde::de::DeserializeOwned;
+use serde_json;
+use stdweb::{Reference, Value};
+use stdweb::unstable::TryInto;
+use stdweb::web::event::{DataTransfer, IEvent};
+use format::Format;
+
+pub use html::{
+    ChangeData,
+    InputData,
+};
+
+pub use stdweb::web::event::{
+    BlurEvent,
+    ClickEvent,
+    ContextMenuEvent,
+    DoubleClickEvent,
+    DragDropEvent,
+    DragEndEvent,
+    DragEnterEvent,
+    DragEvent,
+    DragExitEvent,
+    DragLeaveEvent,
+    DragOverEvent,
+    DragStartEvent,
+    FocusEvent,
+    GotPointerCaptureEvent,
+    IKeyboardEvent,
+    IMouseEvent,
+    IPointerEvent,
+    ITouchEvent,
+    KeyDownEvent,
+    KeyPressEvent,
+    KeyUpEvent,
+    LostPointerCaptureEvent,
+    MouseDownEvent,
+    MouseMoveEvent,
+    MouseOutEvent,
+    MouseEnterEvent,
+    MouseLeaveEvent,
+    MouseOverEvent,
+    MouseUpEvent,
+    MouseWheelEvent,
+    PointerCancelEvent,
+    PointerDownEvent,
+    PointerEnterEvent,
+    PointerLeaveEvent,
+    PointerMoveEvent,
+    PointerOutEvent,
+    PointerOverEvent,
+    PointerUpEvent,
+    ResourceErrorEvent,
+    ResourceLoadEvent,
+    ScrollEvent,
+    SubmitEvent,
+    TouchCancel,
+    TouchEnd,
+    TouchMove,
+    TouchStart,
+};
+
+pub use stdweb::web::{Touch, TouchType};
+
+/// An interface of events of CSS animations. Fields of the event
+/// are `None` if the browser doesn't provide them.
+///
+/// [(JavaScript docs)](https://developer.mozilla.org/en-US/docs/Web/API/AnimationEvent)
+pub trait IAnimationEvent: IEvent {
+    /// Returns the value of `animation-name` property of the animation.
+    fn animation_name(&self) -> Option<String> {
+        js!(
+            return @{self.as_ref()}.animationName;
+        ).try_into().ok()
+    }
+
+    /// Returns the time in seconds the animation has been running.
+    fn elapsed_time(&self) -> Option<f64> {
+        js!(
+            return @{self.as_ref()}.elapsedTime;
+        ).try_into().ok()
+    }
+
+    /// Returns the name of a pseudo-element the animation runs on
+    /// or an empty string if it runs on the element itself.
+    fn pseudo_element(&self) -> Option<String> {
+        js!(
+            return @{self.as_ref()}.pseudoElement;
+        ).try_into().ok()
+    }
+}
+
+/// The `AnimationStartEvent` is fired when a CSS animation has started.
+///
+/// [(JavaScript docs)](https://developer.mozilla.org/en-US/docs/Web/Events/animationstart)
+#[derive(Clone, Debug, PartialEq, Eq, ReferenceType)]
+#[reference(instance_of = "AnimationEvent")]
+#[reference(event = "animationstart")]
+pub struct AnimationStartEvent(Reference);
+
+impl IEvent for AnimationStartEvent {}
+impl IAnimationEvent for AnimationStartEvent {}
+
+/// The `AnimationEndEvent` is fired when a CSS animation has completed.
+///
+/// [(JavaScript docs)](https://developer.mozilla.org/en-US/docs/Web/Events/animationend)
+#[derive(Clone, Debug, PartialEq, Eq, ReferenceType)]
+#[reference(instance_of = "AnimationEvent")]
+#[reference(event = "animationend")]
+pub struct AnimationEndEvent(Reference);
+
+impl IEvent for AnimationEndEvent {}
+impl IAnimationEvent for AnimationEndEvent {}
+
+/// The `AnimationIterationEvent` is fired when an iteration of a CSS animation ends
+/// and another one begins.
+///
+/// [(JavaScript docs)](https://developer.mozilla.org/en-US/docs/Web/Events/animationiteration)
+#[derive(Clone, Debug, PartialEq, Eq, ReferenceType)]
+#[reference(instance_of = "AnimationEvent")]
+#[reference(event = "animationiteration")]
+pub struct AnimationIterationEvent(Reference);
+
+impl IEvent for AnimationIterationEvent {}
+impl IAnimationEvent for AnimationIterationEvent {}
+
+/// The `TransitionEndEvent` is fired when a CSS transition has completed.
+/// Its accessors return `None` if the event lacks the field.
+///
+/// [(JavaScript docs)](https://developer.mozilla.org/en-US/docs/Web/Events/transitionend)
+#[derive(Clone, Debug, PartialEq, Eq, ReferenceType)]
+#[reference(instance_of = "TransitionEvent")]
+#[reference(event = "transitionend")]
+pub struct TransitionEndEvent(Reference);
+
+impl IEvent for TransitionEndEvent {}
+
+impl TransitionEndEvent {
+    /// Returns the name of the CSS property of the transition.
+    pub fn property_name(&self) -> Option<String> {
+        js!(
+            return @{self.as_ref()}.propertyName;
+        ).try_into().ok()
+    }
+
+    /// Returns the time in seconds the transition has been running.
+    pub fn elapsed_time(&self) -> Option<f64> {
+        js!(
+            return @{self.as_ref()}.elapsedTime;
+        ).try_into().ok()
+    }
+}
+
+/// An interface of events of the clipboard.
+///
+/// [(JavaScript docs)](https://developer.mozilla.org/en-US/docs/Web/API/ClipboardEvent)
+pub trait IClipboardEvent: IEvent {
+    /// Returns the data of the clipboard. Handlers of `copy` and `cut` events
+    /// could replace it, `paste` handlers could read it.
+    fn clipboard_data(&self) -> Option<DataTransfer> {
+        js!(
+            return @{self.as_ref()}.clipboardData;
+        ).try_into().ok()
+    }
+}
+
+/// The `CopyEvent` is fired when the user initiates a copy action.
+///
+/// [(JavaScript docs)](https://developer.mozilla.org/en-US/docs/Web/Events/copy)
+#[derive(Clone, Debug, PartialEq, Eq, ReferenceType)]
+#[reference(instance_of = "Event")]
+#[reference(event = "copy")]
+pub struct CopyEvent(Reference);
+
+impl IEvent for CopyEvent {}
+impl IClipboardEvent for CopyEvent {}
+
+/// The `CutEvent` is fired when the user initiates a cut action.
+///
+/// [(JavaScript docs)](https://developer.mozilla.org/en-US/docs/Web/Events/cut)
+#[derive(Clone, Debug, PartialEq, Eq, ReferenceType)]
+#[reference(instance_of = "Event")]
+#[reference(event = "cut")]
+pub struct CutEvent(Reference);
+
+impl IEvent for CutEvent {}
+impl IClipboardEvent for CutEvent {}
+
+/// The `PasteEvent` is fired when the user initiates a paste action.
+///
+/// [(JavaScript docs)](https://developer.mozilla.org/en-US/docs/Web/Events/paste)
+#[derive(Clone, Debug, PartialEq, Eq, ReferenceType)]
+#[reference(instance_of = "Event")]
+#[reference(event = "paste")]
+pub struct PasteEvent(Reference);
+
+impl IEvent for PasteEvent {}
+impl IClipboardEvent for PasteEvent {}
+
+/// An interface of events of the text composition (input methods of IME).
+///
+/// [(JavaScript docs)](https://developer.mozilla.org/en-US/docs/Web/API/CompositionEvent)
+pub trait ICompositionEvent: IEvent {
+    /// Returns the characters generated by the input method
+    /// or `None` if the browser doesn't provide them.
+    fn data(&self) -> Option<String> {
+        js!(
+            return @{self.as_ref()}.data;
+        ).try_into().ok()
+    }
+}
+
+/// The `CompositionStartEvent` is fired when a composition of a text is started.
+///
+/// [(JavaScript docs)](https://developer.mozilla.org/en-US/docs/Web/Events/compositionstart)
+#[derive(Clone, Debug, PartialEq, Eq, ReferenceType)]
+#[reference(instance_of = "CompositionEvent")]
+#[reference(event = "compositionstart")]
+pub struct CompositionStartEvent(Reference);
+
+impl IEvent for CompositionStartEvent {}
+impl ICompositionEvent for CompositionStartEvent {}
+
+/// The `CompositionUpdateEvent` is fired when a new character is received
+/// in the context of a composition.
+///
+/// [(JavaScript docs)](https://developer.mozilla.org/en-US/docs/Web/Events/compositionupdate)
+#[derive(Clone, Debug, PartialEq, Eq, ReferenceType)]
+#[reference(instance_of = "CompositionEvent")]
+#[reference(event = "compositionupdate")]
+pub struct CompositionUpdateEvent(Reference);
+
+impl IEvent for CompositionUpdateEvent {}
+impl ICompositionEvent for CompositionUpdateEvent {}
+
+/// The `CompositionEndEvent` is fired when a composition of a text is completed or cancelled.
+///
+/// [(JavaScript docs)](https://developer.mozilla.org/en-US/docs/Web/Events/compositionend)
+#[derive(Clone, Debug, PartialEq, Eq, ReferenceType)]
+#[reference(instance_of = "CompositionEvent")]
+#[reference(event = "compositionend")]
+pub struct CompositionEndEvent(Reference);
+
+impl IEvent for CompositionEndEvent {}
+impl ICompositionEvent for CompositionEndEvent {}
+
+/// An event with an arbitrary name. Listeners of such events are added with
+/// `on:` prefix in `html!` template: `<my-slider on:value-changed=|e| Msg::Changed(e),>`.
+/// Usually it's a `CustomEvent` dispatched by a web component.
+///
+/// [(JavaScript docs)](https://developer.mozilla.org/en-US/docs/Web/API/CustomEvent)
+#[derive(Clone, Debug, PartialEq, Eq, ReferenceType)]
+#[reference(instance_of = "Event")]
+pub struct CustomEvent(Reference);
+
+impl IEvent for CustomEvent {}
+
+impl CustomEvent {
+    /// Returns `detail` field of the event as it is.
+    pub fn detail_value(&self) -> Value {
+        js!(
+            return @{self.as_ref()}.detail;
+        )
+    }
+
+    /// Deserializes `detail` field of the event. The value is converted
+    /// to JSON first, so it has to be serializable with `JSON.stringify`.
+    pub fn detail<T: DeserializeOwned>(&self) -> Format<T> {
+        let json: String = js!(
+            var detail = @{self.as_ref()}.detail;
+            return detail === undefined ? "null" : JSON.stringify(detail);
+        ).try_into()?;
+        Ok(serde_json::from_str(&json)?)
+    }
+}
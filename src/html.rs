@@ -3,11 +3,19 @@
 //! Also this module contains declaration of `Component` trait which used
 //! to create own UI-components.
 
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::hash::Hash;
+use std::iter::FromIterator;
 use std::rc::Rc;
 use std::cell::RefCell;
-use stdweb::web::{Element, EventListenerHandle, FileList, INode, Node};
+use stdweb::unstable::TryFrom;
+use stdweb::web::{Element, FileList, INode, Node};
+use stdweb::web::event::IEvent;
 use stdweb::web::html_element::SelectElement;
-use virtual_dom::{Listener, VDiff, VNode};
+use events::CustomEvent;
+use virtual_dom::{AttachedListener, Delegation, Listener, ListenerOptions, VChild, VDiff, VNode};
+use virtual_dom::vforeign::MovesTracker;
 use callback::Callback;
 use scheduler::{Runnable, Shared, scheduler};
 
@@ -21,8 +29,8 @@ pub trait Component: Sized + 'static {
     /// Properties type of component implementation.
     /// It sould be serializable because it's sent to dynamicaly created
     /// component (layed under `VComp`) and must be restored for a component
-    /// with unknown type.
-    type Properties: Clone + PartialEq + Default;
+    /// with unknown type. Use `#[derive(Properties)]` to implement it for a struct.
+    type Properties: Properties;
     /// Initialization routine which could use a context.
     fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self;
     /// Called everytime when a messages of `Msg` type received. It also takes a
@@ -32,8 +40,83 @@ pub trait Component: Sized + 'static {
     fn change(&mut self, _: Self::Properties) -> ShouldRender {
         unimplemented!("you should implement `change` method for a component with properties")
     }
+    /// Called after the view of the component is rendered to the DOM: once after
+    /// the first rendering (`first_render` is `true`) and after every re-rendering.
+    /// Use it to work with elements through `NodeRef`s. Messages sent from
+    /// this method are processed by the scheduler later. Return `true` to render
+    /// the component again.
+    fn rendered(&mut self, _first_render: bool) -> ShouldRender {
+        false
+    }
     /// Called for finalization on the final point of the component's lifetime.
-    fn destroy(&mut self) { } // TODO Replace with `Drop`
+    /// The component is dropped right after this call, so tasks kept by it
+    /// are cancelled and `Drop` could be used instead of this method.
+    fn destroy(&mut self) { }
+}
+
+/// Properties of a component. `html!` sets them through a builder, which is
+/// generated by `#[derive(Properties)]`: the builder has a method for every field
+/// and `build` method which is available when all required fields are set.
+pub trait Properties: Clone + PartialEq {
+    /// The builder of properties.
+    type Builder;
+    /// Creates a builder with unset properties.
+    fn builder() -> Self::Builder;
+    /// Writes the properties to a dump of a virtual tree (`Debug` of `VNode`), it's `..` by default.
+    /// `#[derive(Properties)]` uses `Debug` of the properties if they implement it.
+    fn fmt_debug(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("..")
+    }
+}
+
+/// Selects `Debug` implementation of properties if they have it. It's used
+/// by `#[derive(Properties)]`: `(&DebugProps(self)).fmt_props(f)` calls the method
+/// of `WithDebug` if it's implemented and the method of `WithoutDebug` otherwise.
+#[doc(hidden)]
+pub struct DebugProps<'a, T: 'a>(pub &'a T);
+
+#[doc(hidden)]
+pub trait WithDebug {
+    fn fmt_props(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result;
+}
+
+impl<'a, T: fmt::Debug> WithDebug for DebugProps<'a, T> {
+    fn fmt_props(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Properties are written to a single line of the tree
+        write!(f, "{:?}", self.0)
+    }
+}
+
+#[doc(hidden)]
+pub trait WithoutDebug {
+    fn fmt_props(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result;
+}
+
+impl<'a, 'b, T> WithoutDebug for &'b DebugProps<'a, T> {
+    fn fmt_props(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("..")
+    }
+}
+
+/// The builder of components without properties.
+#[derive(Debug)]
+pub struct EmptyBuilder;
+
+impl EmptyBuilder {
+    /// Returns empty properties.
+    pub fn build(self) {}
+}
+
+impl Properties for () {
+    type Builder = EmptyBuilder;
+
+    fn builder() -> Self::Builder {
+        EmptyBuilder
+    }
+
+    fn fmt_debug(&self, _: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Ok(())
+    }
 }
 
 /// Should be rendered relative to context and component environment.
@@ -50,8 +133,11 @@ pub(crate) enum ComponentUpdate< COMP: Component> {
     Message(COMP::Message),
     /// Wraps properties for a component.
     Properties(COMP::Properties),
-    /// Removes the component
-    Destroy,
+    /// Renders the component again
+    Render,
+    /// Removes the component. The flag is `false` if the nodes of the component
+    /// were removed with a parent element already and only have to be released.
+    Destroy(bool),
 }
 
 /// Link to component's scope for creating callbacks.
@@ -64,7 +150,7 @@ where
     COMP: Component + Renderable<COMP>,
 {
     /// Create link for a scope.
-    fn connect(scope: &Scope<COMP>) -> Self {
+    pub(crate) fn connect(scope: &Scope<COMP>) -> Self {
         ComponentLink {
             scope: scope.clone(),
         }
@@ -93,16 +179,31 @@ where
 /// Mostly services uses it.
 pub struct Scope<COMP: Component> {
     shared_component: Shared<Option<ComponentRunnable<COMP>>>,
+    delegation: Option<Delegation>,
 }
 
 impl<COMP: Component> Clone for Scope<COMP> {
     fn clone(&self) -> Self {
         Scope {
             shared_component: self.shared_component.clone(),
+            delegation: self.delegation.clone(),
         }
     }
 }
 
+impl<COMP: Component> Scope<COMP> {
+    /// Returns a registry of delegated listeners if the app uses delegation.
+    pub(crate) fn delegation(&self) -> Option<&Delegation> {
+        self.delegation.as_ref()
+    }
+
+    /// Sets a registry which listeners of the component and its children are delegated to.
+    pub(crate) fn with_delegation(mut self, delegation: Option<Delegation>) -> Self {
+        self.delegation = delegation;
+        self
+    }
+}
+
 impl<COMP> Scope<COMP>
 where
     COMP: Component + Renderable<COMP>,
@@ -117,6 +218,26 @@ where
         scheduler().put_and_try_run(runnable);
     }
 
+    /// Destroys the component immediately, even if the scheduler is busy,
+    /// so messages queued for it are dropped. It's used by a parent to remove
+    /// the nodes of the component in time. Nodes are not detached if `detach`
+    /// is `false`, because they were removed with a parent element.
+    ///
+    /// A component which is busy with its own routine can't be destroyed
+    /// inside of it, so it's destroyed after the routine.
+    pub(crate) fn destroy(&mut self, detach: bool) {
+        let envelope = ComponentEnvelope {
+            shared_component: self.shared_component.clone(),
+            message: Some(ComponentUpdate::Destroy(detach)),
+        };
+        let busy = self.shared_component.try_borrow_mut().is_err();
+        if busy {
+            scheduler().put_and_try_run(Box::new(envelope));
+        } else {
+            scheduler().run_now(Box::new(envelope));
+        }
+    }
+
     /// Send message to a component.
     pub fn send_message(&mut self, message: COMP::Message) {
         let update = ComponentUpdate::Message(message);
@@ -124,8 +245,47 @@ where
     }
 }
 
-/// Holder for the element.
-pub type NodeCell = Rc<RefCell<Option<Node>>>;
+/// Holder for the nodes of a component.
+pub type NodeCell = Rc<RefCell<Option<NodeBounds>>>;
+
+/// The first and the last DOM nodes of a component. They are the same
+/// node unless the component renders a fragment.
+#[derive(Clone)]
+pub struct NodeBounds {
+    first: NodeBound,
+    last: NodeBound,
+}
+
+impl NodeBounds {
+    pub(crate) fn new(first: NodeBound, last: NodeBound) -> Self {
+        NodeBounds { first, last }
+    }
+
+    /// Returns the first DOM node of a component.
+    pub fn first(&self) -> Option<Node> {
+        match self.first {
+            NodeBound::Node(ref node) => Some(node.to_owned()),
+            NodeBound::Cell(ref cell) => cell.borrow().as_ref().and_then(NodeBounds::first),
+        }
+    }
+
+    /// Returns the last DOM node of a component.
+    pub fn last(&self) -> Option<Node> {
+        match self.last {
+            NodeBound::Node(ref node) => Some(node.to_owned()),
+            NodeBound::Cell(ref cell) => cell.borrow().as_ref().and_then(NodeBounds::last),
+        }
+    }
+}
+
+/// A bound of the nodes of a component. If a nested component is at the edge
+/// of the view, the bound refers to the cell of it, because the nested component
+/// replaces its nodes when it's rendered again without rendering of the parent.
+#[derive(Clone)]
+pub(crate) enum NodeBound {
+    Node(Node),
+    Cell(NodeCell),
+}
 
 impl<COMP> Scope<COMP>
 where
@@ -133,7 +293,10 @@ where
 {
     pub(crate) fn new() -> Self {
         let shared_component = Rc::new(RefCell::new(None));
-        Scope { shared_component }
+        Scope {
+            shared_component,
+            delegation: None,
+        }
     }
 
     // TODO Consider to use &Node instead of Element as parent
@@ -143,17 +306,19 @@ where
         element: Element,
         ancestor: Option<VNode<COMP>>,
         occupied: Option<NodeCell>,
-        init_props: Option<COMP::Properties>,
+        init_props: COMP::Properties,
     ) -> Scope<COMP> {
         let runnable = ComponentRunnable {
-            env: self.clone(),
             component: None,
             last_frame: None,
             element,
             ancestor,
             occupied,
-            init_props,
+            init_props: Some(init_props),
+            hydrate: None,
+            delegation: self.delegation.clone(),
             destroyed: false,
+            dropped_messages: 0,
         };
         let mut scope = self.clone();
         *scope.shared_component.borrow_mut() = Some(runnable);
@@ -161,17 +326,55 @@ where
         scope.send(ComponentUpdate::Create(link));
         scope
     }
+
+    /// Mounts elements over existing DOM nodes (rendered on a server)
+    /// starting from the `cursor` node.
+    ///
+    /// Unlike `mount_in_place` the component is created immediately, because
+    /// a parent continues to hydrate the nodes which follow the nodes of this component.
+    pub(crate) fn hydrate_in_place(
+        self,
+        element: Element,
+        cursor: Option<Node>,
+        occupied: Option<NodeCell>,
+        init_props: COMP::Properties,
+    ) -> Scope<COMP> {
+        let runnable = ComponentRunnable {
+            component: None,
+            last_frame: None,
+            element,
+            ancestor: None,
+            occupied,
+            init_props: Some(init_props),
+            hydrate: Some(cursor),
+            delegation: self.delegation.clone(),
+            destroyed: false,
+            dropped_messages: 0,
+        };
+        *self.shared_component.borrow_mut() = Some(runnable);
+        let link = ComponentLink::connect(&self);
+        let envelope = ComponentEnvelope {
+            shared_component: self.shared_component.clone(),
+            message: Some(ComponentUpdate::Create(link)),
+        };
+        scheduler().run_now(Box::new(envelope));
+        self
+    }
 }
 
 struct ComponentRunnable<COMP: Component> {
-    env: Scope<COMP>,
     component: Option<COMP>,
     last_frame: Option<VNode<COMP>>,
     element: Element,
     ancestor: Option<VNode<COMP>>,
     occupied: Option<NodeCell>,
     init_props: Option<COMP::Properties>,
+    /// A DOM node to start hydration from, if the component hydrates existing nodes.
+    hydrate: Option<Option<Node>>,
+    delegation: Option<Delegation>,
     destroyed: bool,
+    /// Count of messages which were sent to the destroyed component.
+    dropped_messages: usize,
 }
 
 /// Wraps a component reference and a message to hide it under `Runnable` trait.
@@ -190,31 +393,53 @@ where
 {
     fn run(&mut self) {
         let mut component = self.shared_component.borrow_mut();
-        let this = component.as_mut().expect("shared component not set");
+        let this = match component.as_mut() {
+            Some(this) => this,
+            None => {
+                // The scope was never mounted. For example, it was created
+                // for rendering to a string on a server.
+                warn!("message sent to a component which was not mounted");
+                return;
+            }
+        };
         if this.destroyed {
+            this.dropped_messages += 1;
+            warn!(
+                "message sent to a destroyed component was ignored ({} dropped)",
+                this.dropped_messages
+            );
             return;
         }
+        // Foreign nodes which are moved by the render keep their owners unaware
+        let _moves = MovesTracker::start();
         let mut should_update = false;
+        let mut first_render = false;
         let upd = self.message.take().expect("component's envelope called twice");
         // This loop pops one item, because the following
         // updates could try to borrow the same cell
         // Important! Don't use `while let` here, because it
         // won't free the lock.
-        let env = this.env.clone();
+        let env = Scope {
+            shared_component: self.shared_component.clone(),
+            delegation: this.delegation.clone(),
+        };
         match upd {
             ComponentUpdate::Create(link) => {
-                let props = this.init_props.take().unwrap_or_default();
+                let props = this.init_props.take().expect("component was created twice");
                 this.component = Some(COMP::create(props, link));
                 // No messages at start
                 let current_frame = this.component.as_ref().unwrap().view();
                 this.last_frame = Some(current_frame);
                 // First-time rendering the tree
-                let node = this.last_frame.as_mut()
-                    .unwrap()
-                    .apply(this.element.as_node(), None, this.ancestor.take(), &env);
+                let frame = this.last_frame.as_mut().unwrap();
+                match this.hydrate.take() {
+                    Some(mut cursor) => frame.hydrate(this.element.as_node(), &mut cursor, &env),
+                    None => frame.apply(this.element.as_node(), None, this.ancestor.take(), &env),
+                };
                 if let Some(ref mut cell) = this.occupied {
-                    *cell.borrow_mut() = node;
+                    *cell.borrow_mut() = frame.bounds();
                 }
+                first_render = true;
             }
             ComponentUpdate::Message(msg) => {
                 should_update |= this.component.as_mut()
@@ -226,40 +451,125 @@ where
                     .expect("component was not created to process properties")
                     .change(props);
             }
-            ComponentUpdate::Destroy => {
-                // TODO this.component.take() instead of destroyed
-                this.component.as_mut().unwrap().destroy();
-                this.last_frame.as_mut().unwrap().detach(this.element.as_node());
+            ComponentUpdate::Render => {
+                should_update = true;
+            }
+            ComponentUpdate::Destroy(detach) => {
+                // The component is dropped here with all its tasks and
+                // callbacks, only the destroyed flag is kept by the scope.
+                if let Some(mut component) = this.component.take() {
+                    component.destroy();
+                }
+                if let Some(mut last_frame) = this.last_frame.take() {
+                    if detach {
+                        last_frame.detach(this.element.as_node());
+                    } else {
+                        last_frame.release();
+                    }
+                } else if let Some(mut ancestor) = this.ancestor.take() {
+                    // The component was not created, remove a node which it had to replace
+                    if detach {
+                        ancestor.detach(this.element.as_node());
+                    }
+                }
+                if let Some(cell) = this.occupied.take() {
+                    *cell.borrow_mut() = None;
+                }
+                this.init_props = None;
                 this.destroyed = true;
+                return;
             }
         }
         if should_update {
             let mut next_frame = this.component.as_ref().unwrap().view();
             // Re-rendering the tree
-            let node =
-                next_frame.apply(this.element.as_node(), None, this.last_frame.take(), &env);
+            next_frame.apply(this.element.as_node(), None, this.last_frame.take(), &env);
             if let Some(ref mut cell) = this.occupied {
-                *cell.borrow_mut() = node;
+                *cell.borrow_mut() = next_frame.bounds();
             }
             this.last_frame = Some(next_frame);
         }
+        if first_render || should_update {
+            let render_again = this.component.as_mut()
+                .expect("component was not created to be rendered")
+                .rendered(first_render);
+            if render_again {
+                // Messages sent by the hook have to be processed before
+                env.clone().send(ComponentUpdate::Render);
+            }
+        }
     }
 }
 
 /// A type which expected as a result of `view` function implementation.
 pub type Html<MSG> = VNode<MSG>;
 
+/// Children of a component which are passed to it with `children` property.
+/// They are set with the nested nodes of a component tag in `html!` template:
+/// `<Card: title="Title",><p>{ &self.text }</p></Card>`. The nodes are created
+/// by the view of the parent, so they can use its fields, and their listeners
+/// send messages to the parent. The component renders them where it needs.
+#[derive(Clone, Default, PartialEq)]
+pub struct Children {
+    nodes: Vec<VChild>,
+}
+
+impl Children {
+    pub(crate) fn new(nodes: Vec<VChild>) -> Self {
+        Children { nodes }
+    }
+
+    /// Returns the count of children.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns `true` if there are no children.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Returns the children to render in a template: `{ for self.props.children.iter() }`.
+    pub fn iter(&self) -> impl Iterator<Item = VChild> + '_ {
+        self.nodes.iter().cloned()
+    }
+}
+
+impl fmt::Debug for Children {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(&self.nodes).finish()
+    }
+}
+
+/// Types of events which are provided by `stdweb` and by `events` module.
+mod event_types {
+    pub use stdweb::web::event::*;
+    pub use events::*;
+}
+
 macro_rules! impl_action {
     ($($action:ident($event:ident : $type:ident) -> $ret:ty => $convert:expr)*) => {$(
         /// An abstract implementation of a listener.
         pub mod $action {
-            use stdweb::web::{IEventTarget, Element};
-            use stdweb::web::event::{IEvent, $type};
+            use stdweb::web::Element;
+            use stdweb::web::event::{ConcreteEvent, IEvent};
+            use super::event_types::$type;
             use super::*;
 
             /// A wrapper for a callback.
             /// Listener extracted from here when attached.
-            pub struct Wrapper<F>(Option<F>);
+            pub struct Wrapper<F> {
+                handler: Option<F>,
+                options: ListenerOptions,
+            }
+
+            impl<F> Wrapper<F> {
+                /// Sets options of the listener.
+                pub fn with_options(mut self, options: ListenerOptions) -> Self {
+                    self.options = options;
+                    self
+                }
+            }
 
             /// And event type which keeps the returned type.
             pub type Event = $ret;
@@ -270,10 +580,16 @@ macro_rules! impl_action {
                 F: Fn($ret) -> MSG + 'static,
             {
                 fn from(handler: F) -> Self {
-                    Wrapper(Some(handler))
+                    Wrapper {
+                        handler: Some(handler),
+                        options: ListenerOptions::default(),
+                    }
                 }
             }
 
+            /// A slot which keeps a handler of an attached listener.
+            type Slot<COMP> = RefCell<Box<dyn Fn($ret) -> <COMP as Component>::Message>>;
+
             impl<T, COMP> Listener<COMP> for Wrapper<T>
             where
                 T: Fn($ret) -> COMP::Message + 'static,
@@ -283,18 +599,51 @@ macro_rules! impl_action {
                     stringify!($action)
                 }
 
-                fn attach(&mut self, element: &Element, mut activator: Scope<COMP>)
-                    -> EventListenerHandle {
-                    let handler = self.0.take().expect("tried to attach listener twice");
+                fn attach(&mut self, element: &Element, activator: Scope<COMP>)
+                    -> AttachedListener {
+                    let handler = self.handler.take().expect("tried to attach listener twice");
+                    let slot: Rc<Slot<COMP>> = Rc::new(RefCell::new(Box::new(handler)));
+                    let delegation = activator.delegation().cloned();
+                    let options = self.options;
                     let this = element.clone();
-                    let listener = move |event: $type| {
-                        debug!("Event handler: {}", stringify!($type));
-                        event.stop_propagation();
-                        let handy_event: $ret = $convert(&this, event);
-                        let msg = handler(handy_event);
-                        activator.send_message(msg);
+                    let listener = {
+                        let slot = slot.clone();
+                        move |event: $type| {
+                            debug!("Event handler: {}", stringify!($type));
+                            if options.prevent_default {
+                                event.prevent_default();
+                            }
+                            if options.stop_propagation {
+                                event.stop_propagation();
+                            }
+                            let handy_event: $ret = $convert(&this, event);
+                            // The slot is released before sending, because
+                            // the sending could render and reattach listeners
+                            let msg = (slot.borrow())(handy_event);
+                            activator.clone().send_message(msg);
+                        }
                     };
-                    element.add_event_listener(listener)
+                    AttachedListener::listen(
+                        stringify!($action),
+                        <$type as ConcreteEvent>::EVENT_TYPE,
+                        element,
+                        options,
+                        delegation.as_ref(),
+                        listener,
+                        slot,
+                    )
+                }
+
+                fn reattach(&mut self, attached: &AttachedListener) -> bool {
+                    match attached.slot::<Slot<COMP>>() {
+                        Some(slot) if attached.kind() == stringify!($action)
+                            && attached.options() == self.options => {
+                            let handler = self.handler.take().expect("tried to attach listener twice");
+                            *slot.borrow_mut() = Box::new(handler);
+                            true
+                        }
+                        _ => false,
+                    }
                 }
             }
         }
@@ -302,6 +651,7 @@ macro_rules! impl_action {
 }
 
 // Inspired by: http://package.elm-lang.org/packages/elm-lang/html/2.0.0/Html-Events
+// `html!` macro treats these names as listeners, keep `LISTENERS` of `yew-macro` in sync
 impl_action! {
     onclick(event: ClickEvent) -> ClickEvent => |_, event| { event }
     ondoubleclick(event: DoubleClickEvent) -> DoubleClickEvent => |_, event| { event }
@@ -339,6 +689,22 @@ impl_action! {
     ondragexit(event: DragExitEvent) -> DragExitEvent => |_, event| { event }
     ondrop(event: DragDropEvent) -> DragDropEvent => |_, event| { event }
     oncontextmenu(event: ContextMenuEvent) -> ContextMenuEvent => |_, event| { event }
+    ontouchstart(event: TouchStart) -> TouchStart => |_, event| { event }
+    ontouchmove(event: TouchMove) -> TouchMove => |_, event| { event }
+    ontouchend(event: TouchEnd) -> TouchEnd => |_, event| { event }
+    ontouchcancel(event: TouchCancel) -> TouchCancel => |_, event| { event }
+    onanimationstart(event: AnimationStartEvent) -> AnimationStartEvent => |_, event| { event }
+    onanimationend(event: AnimationEndEvent) -> AnimationEndEvent => |_, event| { event }
+    onanimationiteration(event: AnimationIterationEvent) -> AnimationIterationEvent => |_, event| { event }
+    ontransitionend(event: TransitionEndEvent) -> TransitionEndEvent => |_, event| { event }
+    oncopy(event: CopyEvent) -> CopyEvent => |_, event| { event }
+    oncut(event: CutEvent) -> CutEvent => |_, event| { event }
+    onpaste(event: PasteEvent) -> PasteEvent => |_, event| { event }
+    oncompositionstart(event: CompositionStartEvent) -> CompositionStartEvent => |_, event| { event }
+    oncompositionupdate(event: CompositionUpdateEvent) -> CompositionUpdateEvent => |_, event| { event }
+    oncompositionend(event: CompositionEndEvent) -> CompositionEndEvent => |_, event| { event }
+    onload(event: ResourceLoadEvent) -> ResourceLoadEvent => |_, event| { event }
+    onerror(event: ResourceErrorEvent) -> ResourceErrorEvent => |_, event| { event }
     oninput(event: InputEvent) -> InputData => |this: &Element, _| {
         use stdweb::web::html_element::{InputElement, TextAreaElement};
         use stdweb::unstable::TryInto;
@@ -396,6 +762,86 @@ impl_action! {
     }
 }
 
+/// A listener of events with an arbitrary name. `html!` adds it for `on:name=` attributes.
+pub struct CustomListener<F> {
+    event_type: &'static str,
+    handler: Option<F>,
+    options: ListenerOptions,
+}
+
+impl<F> CustomListener<F> {
+    /// Creates a listener of events of `event_type`.
+    pub fn new(event_type: &'static str, handler: F) -> Self {
+        CustomListener {
+            event_type,
+            handler: Some(handler),
+            options: ListenerOptions::default(),
+        }
+    }
+
+    /// Sets options of the listener.
+    pub fn with_options(mut self, options: ListenerOptions) -> Self {
+        self.options = options;
+        self
+    }
+}
+
+/// A slot which keeps a handler of an attached custom listener.
+type CustomSlot<COMP> = RefCell<Box<dyn Fn(CustomEvent) -> <COMP as Component>::Message>>;
+
+impl<F, COMP> Listener<COMP> for CustomListener<F>
+where
+    F: Fn(CustomEvent) -> COMP::Message + 'static,
+    COMP: Component + Renderable<COMP>,
+{
+    fn kind(&self) -> &'static str {
+        self.event_type
+    }
+
+    fn attach(&mut self, element: &Element, activator: Scope<COMP>) -> AttachedListener {
+        let handler = self.handler.take().expect("tried to attach listener twice");
+        let slot: Rc<CustomSlot<COMP>> = Rc::new(RefCell::new(Box::new(handler)));
+        let options = self.options;
+        let listener = {
+            let slot = slot.clone();
+            move |event: CustomEvent| {
+                debug!("Custom event handler: {}", event.event_type());
+                if options.prevent_default {
+                    event.prevent_default();
+                }
+                if options.stop_propagation {
+                    event.stop_propagation();
+                }
+                let msg = (slot.borrow())(event);
+                activator.clone().send_message(msg);
+            }
+        };
+        AttachedListener::listen(
+            self.event_type,
+            self.event_type,
+            element,
+            options,
+            // Custom events usually don't bubble and can't reach a delegated
+            // listener on the root, so they are always listened natively.
+            None,
+            listener,
+            slot,
+        )
+    }
+
+    fn reattach(&mut self, attached: &AttachedListener) -> bool {
+        match attached.slot::<CustomSlot<COMP>>() {
+            Some(slot) if attached.kind() == self.event_type
+                && attached.options() == self.options => {
+                let handler = self.handler.take().expect("tried to attach listener twice");
+                *slot.borrow_mut() = Box::new(handler);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
 /// A type representing data from `oninput` event.
 #[derive(Debug)]
 pub struct InputData {
@@ -424,6 +870,296 @@ pub enum ChangeData {
     Files(FileList),
 }
 
+/// A reference to the DOM node of a `VTag`. Pass it to an element with `ref=self.node_ref.clone()`
+/// in `html!` template and it will be filled when the element is rendered and cleared
+/// when the element is removed.
+#[derive(Default, Clone)]
+pub struct NodeRef(Rc<RefCell<Option<Node>>>);
+
+impl NodeRef {
+    /// Returns the node if it is rendered.
+    pub fn get(&self) -> Option<Node> {
+        self.0.borrow().clone()
+    }
+
+    /// Returns the node converted to a specific type (`InputElement` for example)
+    /// if it is rendered and has that type.
+    pub fn cast<T: TryFrom<Node>>(&self) -> Option<T> {
+        self.get().and_then(|node| T::try_from(node).ok())
+    }
+
+    pub(crate) fn set(&self, node: Option<Node>) {
+        *self.0.borrow_mut() = node;
+    }
+}
+
+impl PartialEq for NodeRef {
+    fn eq(&self, other: &NodeRef) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl fmt::Debug for NodeRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("NodeRef<_>")
+    }
+}
+
+/// A list of classes of an element. It could be built from optional parts with
+/// `classes!` macro and used as a value of `class` attribute:
+/// `<div class=classes!("btn", (self.active, "active"), self.extra.as_ref()),>`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Classes {
+    set: Vec<String>,
+}
+
+impl Classes {
+    /// Creates an empty list of classes.
+    pub fn new() -> Self {
+        Classes::default()
+    }
+
+    /// Adds classes to the list. A string is split by whitespaces
+    /// and classes which are in the list already are skipped.
+    pub fn push<T: Into<Classes>>(&mut self, classes: T) {
+        for class in classes.into().set {
+            if !self.set.contains(&class) {
+                self.set.push(class);
+            }
+        }
+    }
+
+    /// Returns `true` if the list contains the class.
+    pub fn contains(&self, class: &str) -> bool {
+        self.set.iter().any(|item| item == class)
+    }
+
+    /// Returns `true` if the list is empty.
+    pub fn is_empty(&self) -> bool {
+        self.set.is_empty()
+    }
+
+    /// Returns an iterator over the classes.
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.set.iter().map(String::as_str)
+    }
+}
+
+impl fmt::Display for Classes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.set.join(" "))
+    }
+}
+
+impl<'a> From<&'a str> for Classes {
+    fn from(classes: &'a str) -> Self {
+        let set = classes.split_whitespace().map(String::from).collect();
+        Classes { set }
+    }
+}
+
+impl From<String> for Classes {
+    fn from(classes: String) -> Self {
+        Classes::from(classes.as_str())
+    }
+}
+
+impl<'a> From<&'a String> for Classes {
+    fn from(classes: &'a String) -> Self {
+        Classes::from(classes.as_str())
+    }
+}
+
+impl<'a> From<&'a Classes> for Classes {
+    fn from(classes: &'a Classes) -> Self {
+        classes.clone()
+    }
+}
+
+impl<T: Into<Classes>> From<Option<T>> for Classes {
+    fn from(classes: Option<T>) -> Self {
+        classes.map(Into::into).unwrap_or_default()
+    }
+}
+
+impl<T: Into<Classes>> From<(bool, T)> for Classes {
+    fn from((condition, classes): (bool, T)) -> Self {
+        if condition {
+            classes.into()
+        } else {
+            Classes::new()
+        }
+    }
+}
+
+impl<T: Into<Classes>> From<Vec<T>> for Classes {
+    fn from(classes: Vec<T>) -> Self {
+        classes.into_iter().collect()
+    }
+}
+
+impl<T: Into<Classes>> Extend<T> for Classes {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for classes in iter {
+            self.push(classes);
+        }
+    }
+}
+
+impl<T: Into<Classes>> FromIterator<T> for Classes {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut classes = Classes::new();
+        classes.extend(iter);
+        classes
+    }
+}
+
+/// Properties of an inline style of an element. It could be built with
+/// `Style::new().set("width", format!("{}%", self.progress))` or parsed from
+/// a declaration like `"color: red; width: 50%"` and used as a value of `style` attribute.
+/// Every property is set with `element.style.setProperty` separately, so an update
+/// of a single property doesn't touch others.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Style {
+    properties: Vec<(String, String)>,
+}
+
+impl Style {
+    /// Creates an empty style.
+    pub fn new() -> Self {
+        Style::default()
+    }
+
+    /// Sets a property and returns the style. It's useful to build a style
+    /// in a single expression.
+    pub fn set<N: Into<String>, V: ToString>(mut self, name: N, value: V) -> Self {
+        self.insert(name, value);
+        self
+    }
+
+    /// Sets a property replacing the previous value of it.
+    /// A value could contain `!important` priority at the end.
+    pub fn insert<N: Into<String>, V: ToString>(&mut self, name: N, value: V) {
+        let name = name.into();
+        let value = value.to_string();
+        let name = name.trim();
+        let value = value.trim();
+        if name.is_empty() {
+            return;
+        }
+        match self.properties.iter_mut().find(|(key, _)| key == name) {
+            Some(property) => property.1 = value.to_owned(),
+            None => self.properties.push((name.to_owned(), value.to_owned())),
+        }
+    }
+
+    /// Removes a property and returns its value.
+    pub fn remove(&mut self, name: &str) -> Option<String> {
+        let position = self.properties.iter().position(|(key, _)| key == name)?;
+        Some(self.properties.remove(position).1)
+    }
+
+    /// Returns a value of a property.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.properties
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns `true` if the style has no properties.
+    pub fn is_empty(&self) -> bool {
+        self.properties.is_empty()
+    }
+
+    /// Returns an iterator over names and values of properties.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.properties
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+}
+
+impl fmt::Display for Style {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let declarations = self.iter()
+            .map(|(name, value)| format!("{}: {};", name, value))
+            .collect::<Vec<_>>();
+        f.write_str(&declarations.join(" "))
+    }
+}
+
+impl<'a> From<&'a str> for Style {
+    fn from(declarations: &'a str) -> Self {
+        let mut style = Style::new();
+        for declaration in declarations.split(';') {
+            let mut parts = declaration.splitn(2, ':');
+            if let (Some(name), Some(value)) = (parts.next(), parts.next()) {
+                style.insert(name, value);
+            }
+        }
+        style
+    }
+}
+
+impl From<String> for Style {
+    fn from(declarations: String) -> Self {
+        Style::from(declarations.as_str())
+    }
+}
+
+impl<'a> From<&'a String> for Style {
+    fn from(declarations: &'a String) -> Self {
+        Style::from(declarations.as_str())
+    }
+}
+
+impl<'a> From<&'a Style> for Style {
+    fn from(style: &'a Style) -> Self {
+        style.clone()
+    }
+}
+
+impl<T: Into<Style>> From<Option<T>> for Style {
+    fn from(style: Option<T>) -> Self {
+        style.map(Into::into).unwrap_or_default()
+    }
+}
+
+impl<N: Into<String>, V: ToString> From<Vec<(N, V)>> for Style {
+    fn from(properties: Vec<(N, V)>) -> Self {
+        properties.into_iter().collect()
+    }
+}
+
+impl<N: Into<String> + Ord, V: ToString> From<BTreeMap<N, V>> for Style {
+    fn from(properties: BTreeMap<N, V>) -> Self {
+        properties.into_iter().collect()
+    }
+}
+
+impl<N: Into<String> + Eq + Hash, V: ToString> From<HashMap<N, V>> for Style {
+    fn from(properties: HashMap<N, V>) -> Self {
+        properties.into_iter().collect()
+    }
+}
+
+impl<N: Into<String>, V: ToString> Extend<(N, V)> for Style {
+    fn extend<I: IntoIterator<Item = (N, V)>>(&mut self, iter: I) {
+        for (name, value) in iter {
+            self.insert(name, value);
+        }
+    }
+}
+
+impl<N: Into<String>, V: ToString> FromIterator<(N, V)> for Style {
+    fn from_iter<I: IntoIterator<Item = (N, V)>>(iter: I) -> Self {
+        let mut style = Style::new();
+        style.extend(iter);
+        style
+    }
+}
+
 /// A bridging type for checking `href` attribute value.
 #[derive(Debug)]
 pub struct Href {
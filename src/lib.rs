@@ -57,6 +57,7 @@
 //!
 
 #![deny(missing_docs, bare_trait_objects, anonymous_parameters, elided_lifetimes_in_paths)]
+// Long snippets of `js!` macro of `stdweb` need a raised recursion limit
 #![recursion_limit = "512"]
 
 #[macro_use]
@@ -73,6 +74,10 @@ extern crate anymap;
 extern crate slab;
 #[macro_use]
 extern crate stdweb;
+#[macro_use]
+extern crate stdweb_derive;
+#[macro_use]
+extern crate yew_macro;
 #[cfg(feature = "toml")]
 extern crate toml;
 #[cfg(feature = "yaml")]
@@ -82,6 +87,11 @@ extern crate rmp_serde;
 #[cfg(feature = "cbor")]
 extern crate serde_cbor;
 
+// The code generated by `html!` and `#[derive(Properties)]` refers to items of the framework with `::yew` paths
+extern crate self as yew;
+
+pub use yew_macro::html;
+
 #[macro_use]
 pub mod macros;
 pub mod format;
@@ -93,6 +103,9 @@ pub mod callback;
 pub mod scheduler;
 pub mod agent;
 pub mod components;
+#[cfg(feature = "ssr")]
+pub mod ssr;
+pub mod events;
 
 /// Initializes yew framework. It should be called first.
 pub fn initialize() {
@@ -104,66 +117,19 @@ pub fn run_loop() {
     stdweb::event_loop();
 }
 
-/// Starts an app mounted to a body of the document.
+/// Starts an app mounted to a body of the document. Properties of the component
+/// are created with `Default`, mount a component with required properties with
+/// `App::mount_to_body_with_props`.
 pub fn start_app<COMP>()
 where
     COMP: Component + Renderable<COMP>,
+    COMP::Properties: Default,
 {
     initialize();
     App::<COMP>::new().mount_to_body();
     run_loop();
 }
 
-/// The module that contains all events available in the framework.
-pub mod events {
-    pub use html::{
-        ChangeData,
-        InputData,
-    };
-
-    pub use stdweb::web::event::{
-        BlurEvent,
-        ClickEvent,
-        ContextMenuEvent,
-        DoubleClickEvent,
-        DragDropEvent,
-        DragEndEvent,
-        DragEnterEvent,
-        DragEvent,
-        DragExitEvent,
-        DragLeaveEvent,
-        DragOverEvent,
-        DragStartEvent,
-        FocusEvent,
-        GotPointerCaptureEvent,
-        IKeyboardEvent,
-        IMouseEvent,
-        IPointerEvent,
-        KeyDownEvent,
-        KeyPressEvent,
-        KeyUpEvent,
-        LostPointerCaptureEvent,
-        MouseDownEvent,
-        MouseMoveEvent,
-        MouseOutEvent,
-        MouseEnterEvent,
-        MouseLeaveEvent,
-        MouseOverEvent,
-        MouseUpEvent,
-        MouseWheelEvent,
-        PointerCancelEvent,
-        PointerDownEvent,
-        PointerEnterEvent,
-        PointerLeaveEvent,
-        PointerMoveEvent,
-        PointerOutEvent,
-        PointerOverEvent,
-        PointerUpEvent,
-        ScrollEvent,
-        SubmitEvent
-    };
-}
-
 /// The Yew Prelude
 ///
 /// The purpose of this module is to alleviate imports of many common types:
@@ -174,14 +140,21 @@ pub mod events {
 /// ```
 pub mod prelude {
     pub use html::{
+        Children,
+        Classes,
         Component,
         ComponentLink,
         Href,
         Html,
+        NodeRef,
+        Properties,
         Renderable,
         ShouldRender,
+        Style,
     };
 
+    pub use yew_macro::Properties;
+
     pub use app::App;
 
     pub use callback::Callback;
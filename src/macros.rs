@@ -1,339 +1,70 @@
-//! This module contains macros which implement the `html!` macro and JSX-like templates.
+//! This module contains helpers which are used by the code generated by the `html!` macro
+//! and the `classes!` macro.
+//!
+//! `html!` checks a template during compilation. Unclosed and mismatched tags are reported
+//! with the span of the tag:
+//!
+//! ```compile_fail
+//! # #[macro_use] extern crate yew;
+//! # use yew::prelude::*;
+//! # struct Model;
+//! # impl Component for Model {
+//! #     type Message = ();
+//! #     type Properties = ();
+//! #     fn create(_: (), _: ComponentLink<Self>) -> Self { Model }
+//! #     fn update(&mut self, _: ()) -> ShouldRender { true }
+//! # }
+//! # impl Renderable<Model> for Model {
+//! fn view(&self) -> Html<Self> {
+//!     html! { <div><span></div> }
+//! }
+//! # }
+//! # fn main() {}
+//! ```
+//!
+//! As well as unknown properties of components and handlers which don't accept the event:
+//!
+//! ```compile_fail
+//! # #[macro_use] extern crate yew;
+//! # use yew::prelude::*;
+//! # struct Model;
+//! # impl Component for Model {
+//! #     type Message = ();
+//! #     type Properties = ();
+//! #     fn create(_: (), _: ComponentLink<Self>) -> Self { Model }
+//! #     fn update(&mut self, _: ()) -> ShouldRender { true }
+//! # }
+//! # impl Renderable<Model> for Model {
+//! fn view(&self) -> Html<Self> {
+//!     html! { <button onclick=|e: KeyDownEvent| ()></button> }
+//! }
+//! # }
+//! # fn main() {}
+//! ```
 
 use std::borrow::Cow;
-use html::Component;
-use virtual_dom::{Listener, VNode};
+use html::{Classes, Component, NodeRef, Style};
+use virtual_dom::{Listener, PropValue, VNode};
 
-#[doc(hidden)]
-#[macro_export]
-macro_rules! local_stringify {
-    ($s:ident) => {
-        stringify!($s)
-    }
-}
-
-#[doc(hidden)]
+/// This macro builds a `Classes` list from values which could be converted to it:
+/// strings, `Option`s, `(bool, class)` pairs, `Vec`s and other lists.
+///
+/// ```
+/// # #[macro_use] extern crate yew;
+/// # fn main() {
+/// let active = true;
+/// let extra: Option<&str> = None;
+/// let classes = classes!("btn btn-primary", (active, "active"), extra);
+/// assert_eq!(classes.to_string(), "btn btn-primary active");
+/// # }
+/// ```
 #[macro_export]
-macro_rules! local_vec {
-    [$($e:expr),+] => {
-        vec![$($e,)+]
-    }
-}
-
-/// The html! entrypoint and this implementation had to be separated to prevent infinite recursion.
-#[doc(hidden)]
-#[macro_export(local_inner_macros)]
-macro_rules! html_impl {
-    ($stack:ident (< > $($tail:tt)*)) => {
-        let vlist = $crate::virtual_dom::VList::new();
-        $stack.push(vlist.into());
-        html_impl! { $stack ($($tail)*) }
-    };
-    ($stack:ident (< / > $($tail:tt)*)) => {
-        $crate::macros::child_to_parent(&mut $stack, None);
-        html_impl! { $stack ($($tail)*) }
-    };
-    // Begin component tag
-    // eg: <$type: ..
-    ($stack:ident (< $comp:ty : $($tail:tt)*)) => {
+macro_rules! classes {
+    ($($class:expr),* $(,)*) => {{
         #[allow(unused_mut)]
-        let mut pair = $crate::virtual_dom::VComp::lazy::<$comp>();
-        html_impl! { @vcomp $stack pair ($($tail)*) }
-    };
-    // Set a whole struct as properties
-    // eg: with $ident, ..
-    (@vcomp $stack:ident $pair:ident (with $props:ident, $($tail:tt)*)) => {
-        $pair.0 = $props;
-        html_impl! { @vcomp $stack $pair ($($tail)*) }
-    };
-    // Set a specific field of the properties
-    // It uses `Transformer` trait to convert a type used in template to a type of the field.
-    // eg: $ident = $expr, ..
-    (@vcomp $stack:ident $pair:ident ($attr:ident = $val:expr, $($tail:tt)*)) => {
-        // It cloned for ergonomics in templates. Attribute with
-        // `self.param` value could be reused and sholdn't be cloned
-        // by yourself
-        ($pair.0).$attr = $crate::virtual_dom::vcomp::Transformer::transform(&mut $pair.1, $val);
-        html_impl! { @vcomp $stack $pair ($($tail)*) }
-    };
-    // Tag self-close
-    // eg: /> ..
-    (@vcomp $stack:ident $pair:ident (/ > $($tail:tt)*)) => {
-        let (props, mut comp) = $pair;
-        comp.set_props(props);
-        $stack.push(comp.into());
-        $crate::macros::child_to_parent(&mut $stack, None);
-        html_impl! { $stack ($($tail)*) }
-    };
-    // Begin open svg tag
-    // Sets the xmlns to the svg namespace
-    // eg: <svg ..
-    ($stack:ident (< svg $($tail:tt)*)) => {
-        let vtag = $crate::virtual_dom::VTag::new("svg", Some("http://www.w3.org/2000/svg".into()));
-        $stack.push(vtag.into());
-        html_impl! { @vtag $stack ($($tail)*) }
-    };
-    // Begin open tag
-    // eg: <$ident ..
-    ($stack:ident (< $starttag:ident $($tail:tt)*)) => {
-        let vtag = $crate::virtual_dom::VTag::new(local_stringify!($starttag), None);
-        $stack.push(vtag.into());
-        html_impl! { @vtag $stack ($($tail)*) }
-    };
-    // Set namespace
-    (@vtag $stack:ident (xmlns = $value:expr, $($tail:tt)*)) => {
-        $crate::macros::set_namespace(&mut $stack, Some($value.into()));
-        html_impl! { @vtag $stack ($($tail)*) }
-    };
-
-    // Set multiple classes
-    // PATTERN: class=("class-1", "class-2", local_variable),
-    // eg: class=($expr, ...), ..
-    (@vtag $stack:ident (class = ($($class:expr),*), $($tail:tt)*)) => {
-        $( $crate::macros::append_class(&mut $stack, $class); )*
-        html_impl! { @vtag $stack ($($tail)*) }
-    };
-    // Set a single class
-    // eg: class=$expr, ..
-    (@vtag $stack:ident (class = $class:expr, $($tail:tt)*)) => {
-        $crate::macros::set_classes(&mut $stack, $class);
-        html_impl! { @vtag $stack ($($tail)*) }
-    };
-    // Set value
-    // PATTERN: value="",
-    // eg: value=$expr, ..
-    (@vtag $stack:ident (value = $value:expr, $($tail:tt)*)) => {
-        $crate::macros::set_value_or_attribute(&mut $stack, $value);
-        html_impl! { @vtag $stack ($($tail)*) }
-    };
-    // PATTERN: attribute=value, - workaround for `type` attribute
-    // because `type` is a keyword in Rust
-    // eg: type=$expr, ..
-    (@vtag $stack:ident (type = $kind:expr, $($tail:tt)*)) => {
-        $crate::macros::set_kind(&mut $stack, $kind);
-        html_impl! { @vtag $stack ($($tail)*) }
-    };
-    // eg: checked=$expr, ..
-    (@vtag $stack:ident (checked = $kind:expr, $($tail:tt)*)) => {
-        $crate::macros::set_checked(&mut $stack, $kind);
-        html_impl! { @vtag $stack ($($tail)*) }
-    };
-    // eg: disabled=$expr, ..
-    (@vtag $stack:ident (disabled = $kind:expr, $($tail:tt)*)) => {
-        if $kind {
-            $crate::macros::add_attribute(&mut $stack, "disabled", "true");
-        }
-        html_impl! { @vtag $stack ($($tail)*) }
-    };
-    (@vtag $stack:ident (selected = $kind:expr, $($tail:tt)*)) => {
-        if $kind {
-            $crate::macros::add_attribute(&mut $stack, "selected", "selected");
-        }
-        html_impl! { @vtag $stack ($($tail)*) }
-    };
-    // Events:
-    // TODO Try to rewrite with procedural macro.
-    (@vtag $stack:ident (onclick = | $var:pat | $handler:expr, $($tail:tt)*)) => {
-        html_impl! { @vtag $stack ((onclick) = move | $var: $crate::events::ClickEvent | $handler, $($tail)*) }
-    };
-    (@vtag $stack:ident (ondoubleclick = | $var:pat | $handler:expr, $($tail:tt)*)) => {
-        html_impl! { @vtag $stack ((ondoubleclick) = move | $var: $crate::events::DoubleClickEvent | $handler, $($tail)*) }
-    };
-    (@vtag $stack:ident (onkeypress = | $var:pat | $handler:expr, $($tail:tt)*)) => {
-        html_impl! { @vtag $stack ((onkeypress) = move | $var: $crate::events::KeyPressEvent | $handler, $($tail)*) }
-    };
-    (@vtag $stack:ident (onkeydown = | $var:pat | $handler:expr, $($tail:tt)*)) => {
-        html_impl! { @vtag $stack ((onkeydown) = move | $var: $crate::events::KeyDownEvent | $handler, $($tail)*) }
-    };
-    (@vtag $stack:ident (onkeyup = | $var:pat | $handler:expr, $($tail:tt)*)) => {
-        html_impl! { @vtag $stack ((onkeyup) = move | $var: $crate::events::KeyUpEvent | $handler, $($tail)*) }
-    };
-    (@vtag $stack:ident (onmousedown = | $var:pat | $handler:expr, $($tail:tt)*)) => {
-        html_impl! { @vtag $stack ((onmousedown) = move | $var: $crate::events::MouseDownEvent | $handler, $($tail)*) }
-    };
-    (@vtag $stack:ident (onmousemove = | $var:pat | $handler:expr, $($tail:tt)*)) => {
-        html_impl! { @vtag $stack ((onmousemove) = move | $var: $crate::events::MouseMoveEvent | $handler, $($tail)*) }
-    };
-    (@vtag $stack:ident (onmouseout = | $var:pat | $handler:expr, $($tail:tt)*)) => {
-        html_impl! { @vtag $stack ((onmouseout) = move | $var: $crate::events::MouseOutEvent | $handler, $($tail)*) }
-    };
-    (@vtag $stack:ident (onmouseenter = | $var:pat | $handler:expr, $($tail:tt)*)) => {
-        html_impl! { @vtag $stack ((onmouseenter) = move | $var: $crate::events::MouseEnterEvent | $handler, $($tail)*) }
-    };
-    (@vtag $stack:ident (onmouseleave = | $var:pat | $handler:expr, $($tail:tt)*)) => {
-        html_impl! { @vtag $stack ((onmouseleave) = move | $var: $crate::events::MouseLeaveEvent | $handler, $($tail)*) }
-    };
-    (@vtag $stack:ident (onmousewheel = | $var:pat | $handler:expr, $($tail:tt)*)) => {
-        html_impl! { @vtag $stack ((onmousewheel) = move | $var: $crate::events::MouseWheelEvent | $handler, $($tail)*) }
-    };
-    (@vtag $stack:ident (onmouseover = | $var:pat | $handler:expr, $($tail:tt)*)) => {
-        html_impl! { @vtag $stack ((onmouseover) = move | $var: $crate::events::MouseOverEvent | $handler, $($tail)*) }
-    };
-    (@vtag $stack:ident (onmouseup = | $var:pat | $handler:expr, $($tail:tt)*)) => {
-        html_impl! { @vtag $stack ((onmouseup) = move | $var: $crate::events::MouseUpEvent | $handler, $($tail)*) }
-    };
-    (@vtag $stack:ident (ongotpointercapture = | $var:pat | $handler:expr, $($tail:tt)*)) => {
-        html_impl! { @vtag $stack ((ongotpointercapture) = move | $var: $crate::events::GotPointerCaptureEvent | $handler, $($tail)*) }
-    };
-    (@vtag $stack:ident (onlostpointercapture = | $var:pat | $handler:expr, $($tail:tt)*)) => {
-        html_impl! { @vtag $stack ((onlostpointercapture) = move | $var: $crate::events::LostPointerCaptureEvent | $handler, $($tail)*) }
-    };
-    (@vtag $stack:ident (onpointercancel = | $var:pat | $handler:expr, $($tail:tt)*)) => {
-        html_impl! { @vtag $stack ((onpointercancel) = move | $var: $crate::events::PointerCancelEvent | $handler, $($tail)*) }
-    };
-    (@vtag $stack:ident (onpointerdown = | $var:pat | $handler:expr, $($tail:tt)*)) => {
-        html_impl! { @vtag $stack ((onpointerdown) = move | $var: $crate::events::PointerDownEvent | $handler, $($tail)*) }
-    };
-    (@vtag $stack:ident (onpointerenter = | $var:pat | $handler:expr, $($tail:tt)*)) => {
-        html_impl! { @vtag $stack ((onpointerenter) = move | $var: $crate::events::PointerEnterEvent | $handler, $($tail)*) }
-    };
-    (@vtag $stack:ident (onpointerleave = | $var:pat | $handler:expr, $($tail:tt)*)) => {
-        html_impl! { @vtag $stack ((onpointerleave) = move | $var: $crate::events::PointerLeaveEvent | $handler, $($tail)*) }
-    };
-    (@vtag $stack:ident (onpointermove = | $var:pat | $handler:expr, $($tail:tt)*)) => {
-        html_impl! { @vtag $stack ((onpointermove) = move | $var: $crate::events::PointerMoveEvent | $handler, $($tail)*) }
-    };
-    (@vtag $stack:ident (onpointerout = | $var:pat | $handler:expr, $($tail:tt)*)) => {
-        html_impl! { @vtag $stack ((onpointerout) = move | $var: $crate::events::PointerOutEvent | $handler, $($tail)*) }
-    };
-    (@vtag $stack:ident (onpointerover = | $var:pat | $handler:expr, $($tail:tt)*)) => {
-        html_impl! { @vtag $stack ((onpointerover) = move | $var: $crate::events::PointerOverEvent | $handler, $($tail)*) }
-    };
-    (@vtag $stack:ident (onpointerup = | $var:pat | $handler:expr, $($tail:tt)*)) => {
-        html_impl! { @vtag $stack ((onpointerup) = move | $var: $crate::events::PointerUpEvent | $handler, $($tail)*) }
-    };
-    (@vtag $stack:ident (onscroll = | $var:pat | $handler:expr, $($tail:tt)*)) => {
-        html_impl! { @vtag $stack ((onscroll) = move | $var: $crate::events::ScrollEvent | $handler, $($tail)*) }
-    };
-    (@vtag $stack:ident (onblur = | $var:pat | $handler:expr, $($tail:tt)*)) => {
-        html_impl! { @vtag $stack ((onblur) = move | $var: $crate::events::BlurEvent | $handler, $($tail)*) }
-    };
-    (@vtag $stack:ident (onfocus = | $var:pat | $handler:expr, $($tail:tt)*)) => {
-        html_impl! { @vtag $stack ((onfocus) = move | $var: $crate::events::FocusEvent | $handler, $($tail)*) }
-    };
-    (@vtag $stack:ident (onsubmit = | $var:pat | $handler:expr, $($tail:tt)*)) => {
-        html_impl! { @vtag $stack ((onsubmit) = move | $var: $crate::events::SubmitEvent | $handler, $($tail)*) }
-    };
-    (@vtag $stack:ident (oninput = | $var:pat | $handler:expr, $($tail:tt)*)) => {
-        html_impl! { @vtag $stack ((oninput) = move | $var: $crate::events::InputData | $handler, $($tail)*) }
-    };
-    (@vtag $stack:ident (onchange = | $var:pat | $handler:expr, $($tail:tt)*)) => {
-        html_impl! { @vtag $stack ((onchange) = move | $var: $crate::events::ChangeData | $handler, $($tail)*) }
-    };
-    (@vtag $stack:ident (ondragstart = | $var:pat | $handler:expr, $($tail:tt)*)) => {
-        html_impl! { @vtag $stack ((ondragstart) = move | $var: $crate::events::DragStartEvent | $handler, $($tail)*) }
-    };
-    (@vtag $stack:ident (ondrag = | $var:pat | $handler:expr, $($tail:tt)*)) => {
-        html_impl! { @vtag $stack ((ondrag) = move | $var: $crate::events::DragEvent | $handler, $($tail)*) }
-    };
-    (@vtag $stack:ident (ondragend = | $var:pat | $handler:expr, $($tail:tt)*)) => {
-        html_impl! { @vtag $stack ((ondragend) = move | $var: $crate::events::DragEndEvent | $handler, $($tail)*) }
-    };
-    (@vtag $stack:ident (ondragenter = | $var:pat | $handler:expr, $($tail:tt)*)) => {
-        html_impl! { @vtag $stack ((ondragenter) = move | $var: $crate::events::DragEnterEvent | $handler, $($tail)*) }
-    };
-    (@vtag $stack:ident (ondragleave = | $var:pat | $handler:expr, $($tail:tt)*)) => {
-        html_impl! { @vtag $stack ((ondragleave) = move | $var: $crate::events::DragLeaveEvent | $handler, $($tail)*) }
-    };
-    (@vtag $stack:ident (ondragover = | $var:pat | $handler:expr, $($tail:tt)*)) => {
-        html_impl! { @vtag $stack ((ondragover) = move | $var: $crate::events::DragOverEvent | $handler, $($tail)*) }
-    };
-    (@vtag $stack:ident (ondragexit = | $var:pat | $handler:expr, $($tail:tt)*)) => {
-        html_impl! { @vtag $stack ((ondragexit) = move | $var: $crate::events::DragExitEvent | $handler, $($tail)*) }
-    };
-    (@vtag $stack:ident (ondrop = | $var:pat | $handler:expr, $($tail:tt)*)) => {
-        html_impl! { @vtag $stack ((ondrop) = move | $var: $crate::events::DragDropEvent | $handler, $($tail)*) }
-    };
-    (@vtag $stack:ident (oncontextmenu = | $var:pat | $handler:expr, $($tail:tt)*)) => {
-        html_impl! { @vtag $stack ((oncontextmenu) = move | $var: $crate::events::ContextMenuEvent | $handler, $($tail)*) }
-    };
-
-    // PATTERN: (action)=expression,
-    // eg: ($ident)=$expr, ..
-    (@vtag $stack:ident (($action:ident) = $handler:expr, $($tail:tt)*)) => {
-        // Catch value to a separate variable for clear error messages
-        let handler = $handler;
-        let listener = $crate::html::$action::Wrapper::from(handler);
-        $crate::macros::attach_listener(&mut $stack, Box::new(listener));
-        html_impl! { @vtag $stack ($($tail)*) }
-    };
-    // Attributes
-    // eg: href=$expr, ..
-    (@vtag $stack:ident (href = $href:expr, $($tail:tt)*)) => {
-        let href: $crate::html::Href = $href.into();
-        $crate::macros::add_attribute(&mut $stack, "href", href);
-        html_impl! { @vtag $stack ($($tail)*) }
-    };
-    // eg: $ident=$expr, ..
-    (@vtag $stack:ident ($attr:ident = $val:expr, $($tail:tt)*)) => {
-        $crate::macros::add_attribute(&mut $stack, local_stringify!($attr), $val);
-        html_impl! { @vtag $stack ($($tail)*) }
-    };
-    // Terminate open tag
-    // eg: > ..
-    (@vtag $stack:ident (> $($tail:tt)*)) => {
-        html_impl! { $stack ($($tail)*) }
-    };
-    // Self-close tag
-    // eg: /> ..
-    (@vtag $stack:ident (/ > $($tail:tt)*)) => {
-        $crate::macros::child_to_parent(&mut $stack, None);
-        html_impl! { $stack ($($tail)*) }
-    };
-    (@vtag $stack:ident ($($attr:ident)-+ = $val:expr, $($tail:tt)*)) => {
-        let attr = local_vec![$(local_stringify!($attr).to_string()),+].join("-");
-        $crate::macros::add_attribute(&mut $stack, &attr, $val);
-        html_impl! { @vtag $stack ($($tail)*) }
-    };
-    // Close tag
-    // eg: </$ident> ..
-    ($stack:ident (< / $endtag:ident > $($tail:tt)*)) => {
-        let endtag = local_stringify!($endtag);
-        $crate::macros::child_to_parent(&mut $stack, Some(endtag));
-        html_impl! { $stack ($($tail)*) }
-    };
-    // PATTERN: { for expression }
-    // eg: { for $expr }
-    ($stack:ident ({ for $eval:expr } $($tail:tt)*)) => {
-        let nodes = $eval;
-        let mut vlist = $crate::virtual_dom::VList::new();
-        for node in nodes {
-            let node = $crate::virtual_dom::VNode::from(node);
-            vlist.add_child(node);
-        }
-        $stack.push(vlist.into());
-        $crate::macros::child_to_parent(&mut $stack, None);
-        html_impl! { $stack ($($tail)*) }
-    };
-    // Support root text nodes: #313
-    // Provides `html!` blocks with only expression inside
-    ($stack:ident ({ $eval:expr })) => {
-        let node = $crate::virtual_dom::VNode::from($eval);
-        $stack.push(node);
-        html_impl! { $stack () }
-    };
-    // PATTERN: { expression }
-    // eg: { $expr } ..
-    ($stack:ident ({ $eval:expr } $($tail:tt)*)) => {
-        let node = $crate::virtual_dom::VNode::from($eval);
-        $crate::macros::add_child(&mut $stack, node);
-        html_impl! { $stack ($($tail)*) }
-    };
-    // "End of paring" rule
-    ($stack:ident ()) => {
-        $crate::macros::unpack($stack)
-    };
-    ($stack:ident $($tail:tt)*) => {
-        compile_error!("You should use curly bracets for text nodes: <a>{ \"Link\" }</a>");
-    };
-}
-
-/// This macro implements JSX-like templates.
-#[macro_export]
-macro_rules! html {
-    ($($tail:tt)*) => {{
-        let mut stack = Vec::new();
-        $crate::html_impl! { stack ($($tail)*) }
+        let mut classes = $crate::html::Classes::new();
+        $( classes.push($class); )*
+        classes
     }};
 }
 
@@ -341,12 +72,10 @@ type Stack<COMP> = Vec<VNode<COMP>>;
 
 #[doc(hidden)]
 pub fn unpack<COMP: Component>(mut stack: Stack<COMP>) -> VNode<COMP> {
-    const LENGTH_ERROR: &'static str = "html must contain exactly one element!";
-
-    if stack.len() != 1 {
-        panic!(LENGTH_ERROR);
-    }
-    let mut node = stack.pop().expect(LENGTH_ERROR);
+    // Multiple root nodes are wrapped into a list and every other node is moved
+    // to its parent when it's closed, so only the root is left on the stack
+    assert_eq!(stack.len(), 1, "html! left {} nodes on the stack instead of the root", stack.len());
+    let mut node = stack.pop().expect("html! has no root node");
 
     // Recursively update tag namespaces
     // 1. a parent sets a child's namespace, overriding its parent's namespaces
@@ -361,18 +90,26 @@ pub fn unpack<COMP: Component>(mut stack: Stack<COMP>) -> VNode<COMP> {
                     tag.ns = Some(some_ns.clone());
                 }
 
-                for mut child in &mut tag.childs {
-                    proliferate_namespaces(&mut child, current_ns.clone());
+                for child in &mut tag.childs {
+                    proliferate_namespaces(child, current_ns.clone());
                 }
             },
             VNode::VList(ref mut list) => {
-                for mut child in &mut list.childs {
-                    proliferate_namespaces(&mut child, ns.clone());
+                for child in &mut list.childs {
+                    proliferate_namespaces(child, ns.clone());
                 }
             },
-            VNode::VText(_) | VNode::VComp(_) | VNode::VRef(_) => (),
+            // The node of a portal belongs to its host element and
+            // children belong to the template of the parent
+            VNode::VText(_)
+            | VNode::VComp(_)
+            | VNode::VRaw(_)
+            | VNode::VForeign(_)
+            | VNode::VPortal(_)
+            | VNode::VChild(_)
+            | VNode::VRef(_) => (),
         }
-    };
+    }
 
     proliferate_namespaces(&mut node, None);
     node
@@ -412,6 +149,24 @@ pub fn set_checked<COMP: Component>(stack: &mut Stack<COMP>, value: bool) {
     }
 }
 
+#[doc(hidden)]
+pub fn set_key<COMP: Component, T: ToString>(stack: &mut Stack<COMP>, key: T) {
+    if let Some(&mut VNode::VTag(ref mut vtag)) = stack.last_mut() {
+        vtag.set_key(&key);
+    } else {
+        panic!("no tag to set key: {}", key.to_string());
+    }
+}
+
+#[doc(hidden)]
+pub fn set_node_ref<COMP: Component>(stack: &mut Stack<COMP>, node_ref: NodeRef) {
+    if let Some(&mut VNode::VTag(ref mut vtag)) = stack.last_mut() {
+        vtag.set_node_ref(node_ref);
+    } else {
+        panic!("no tag to set reference: {:?}", node_ref);
+    }
+}
+
 #[doc(hidden)]
 pub fn set_namespace<COMP, NS>(stack: &mut Stack<COMP>, namespace: NS)
 where
@@ -436,20 +191,117 @@ pub fn add_attribute<COMP: Component, T: ToString>(stack: &mut Stack<COMP>, name
 }
 
 #[doc(hidden)]
-pub fn append_class<COMP: Component, T: AsRef<str>>(stack: &mut Stack<COMP>, class: T) {
+pub fn set_attribute<COMP: Component>(stack: &mut Stack<COMP>, name: &str, value: Option<String>) {
+    if let Some(&mut VNode::VTag(ref mut vtag)) = stack.last_mut() {
+        if let Some(value) = value {
+            vtag.add_attribute(name, &value);
+        }
+    } else {
+        panic!("no tag to set attribute: {}", name);
+    }
+}
+
+/// A wrapper of a value of an attribute used by `html!` macro. The macro calls
+/// `(&&AttributeValue(value)).to_attribute()`, so the method is resolved by the type
+/// of the value: `OptionalAttribute` is taken first, then `BooleanAttribute`
+/// and `PlainAttribute` is taken for other values.
+#[doc(hidden)]
+pub struct AttributeValue<T>(pub T);
+
+/// Converts an optional value of an attribute. `None` means that
+/// the attribute is not set.
+#[doc(hidden)]
+pub trait OptionalAttribute {
+    fn to_attribute(&self) -> Option<String>;
+}
+
+impl<'a, T: ToString> OptionalAttribute for &'a AttributeValue<Option<T>> {
+    fn to_attribute(&self) -> Option<String> {
+        self.0.as_ref().map(ToString::to_string)
+    }
+}
+
+/// Converts a `bool` value of an attribute. The attribute is set by presence:
+/// it's set with an empty value if the value is `true` and it's not set otherwise.
+#[doc(hidden)]
+pub trait BooleanAttribute {
+    fn to_attribute(&self) -> Option<String>;
+}
+
+impl<'a, 'b> BooleanAttribute for &'a &'b AttributeValue<bool> {
+    fn to_attribute(&self) -> Option<String> {
+        if self.0 {
+            Some(String::new())
+        } else {
+            None
+        }
+    }
+}
+
+/// Converts a value of an attribute to a string.
+#[doc(hidden)]
+pub trait PlainAttribute {
+    fn to_attribute(&self) -> Option<String>;
+}
+
+impl<T: ToString> PlainAttribute for AttributeValue<T> {
+    fn to_attribute(&self) -> Option<String> {
+        Some(self.0.to_string())
+    }
+}
+
+#[doc(hidden)]
+pub fn append_class<COMP: Component, T: Into<Classes>>(stack: &mut Stack<COMP>, class: T) {
+    if let Some(&mut VNode::VTag(ref mut vtag)) = stack.last_mut() {
+        vtag.add_classes(class);
+    } else {
+        panic!("no tag to attach class: {}", class.into());
+    }
+}
+
+#[doc(hidden)]
+pub fn set_classes<COMP: Component, T: Into<Classes>>(stack: &mut Stack<COMP>, classes: T) {
+    if let Some(&mut VNode::VTag(ref mut vtag)) = stack.last_mut() {
+        vtag.classes.clear();
+        vtag.add_classes(classes);
+    } else {
+        panic!("no tag to set classes: {}", classes.into());
+    }
+}
+
+#[doc(hidden)]
+pub fn set_styles<COMP: Component, T: Into<Style>>(stack: &mut Stack<COMP>, style: T) {
     if let Some(&mut VNode::VTag(ref mut vtag)) = stack.last_mut() {
-        vtag.add_class(class.as_ref());
+        vtag.styles.clear();
+        vtag.add_styles(style);
     } else {
-        panic!("no tag to attach class: {}", class.as_ref());
+        panic!("no tag to set style: {}", style.into());
     }
 }
 
 #[doc(hidden)]
-pub fn set_classes<COMP: Component, T: AsRef<str>>(stack: &mut Stack<COMP>, classes: T) {
+pub fn set_prop<COMP: Component, T: Into<PropValue>>(stack: &mut Stack<COMP>, name: &str, value: T) {
     if let Some(&mut VNode::VTag(ref mut vtag)) = stack.last_mut() {
-        vtag.set_classes(classes.as_ref());
+        vtag.add_prop(name, value);
     } else {
-        panic!("no tag to set classes: {}", classes.as_ref());
+        panic!("no tag to set property: {}", name);
+    }
+}
+
+#[doc(hidden)]
+pub fn set_props<COMP, I, N, T>(stack: &mut Stack<COMP>, props: I)
+where
+    COMP: Component,
+    I: IntoIterator<Item = (N, T)>,
+    N: AsRef<str>,
+    T: Into<PropValue>,
+{
+    if let Some(&mut VNode::VTag(ref mut vtag)) = stack.last_mut() {
+        for (name, value) in props {
+            vtag.add_prop(name.as_ref(), value);
+        }
+    } else {
+        panic!("no tag to set properties");
     }
 }
 
@@ -480,17 +332,27 @@ pub fn add_child<COMP: Component>(stack: &mut Stack<COMP>, child: VNode<COMP>) {
     }
 }
 
+/// Converts a value of a block to a node. It's a function, so the conversion
+/// doesn't trigger lints of the code which uses `html!` when the value is a node already.
 #[doc(hidden)]
-pub fn child_to_parent<COMP: Component>(stack: &mut Stack<COMP>, endtag: Option<&'static str>) {
-    if let Some(mut node) = stack.pop() {
-        // Check the enclosing tag
-        // TODO Check it during compilation. Possible?
-        if let (&mut VNode::VTag(ref mut vtag), Some(endtag)) = (&mut node, endtag) {
-            let starttag = vtag.tag();
-            if !starttag.eq_ignore_ascii_case(endtag) {
-                panic!("wrong closing tag: <{}> -> </{}>", starttag, endtag);
-            }
-        }
+pub fn into_node<COMP: Component, T: Into<VNode<COMP>>>(value: T) -> VNode<COMP> {
+    value.into()
+}
+
+#[doc(hidden)]
+pub fn add_or_push<COMP: Component>(stack: &mut Stack<COMP>, child: VNode<COMP>) {
+    // A node without a parent is the root node of the template
+    if stack.is_empty() {
+        stack.push(child);
+    } else {
+        add_child(stack, child);
+    }
+}
+
+#[doc(hidden)]
+pub fn child_to_parent<COMP: Component>(stack: &mut Stack<COMP>) {
+    // Closing tags are checked by `html!` during compilation
+    if let Some(node) = stack.pop() {
         // Push the popped element to the last in the stack
         if !stack.is_empty() {
             match stack.last_mut() {
@@ -509,6 +371,6 @@ pub fn child_to_parent<COMP: Component>(stack: &mut Stack<COMP>, endtag: Option<
             stack.push(node);
         }
     } else {
-        panic!("redundant closing tag: {:?}", endtag);
+        panic!("redundant closing tag");
     }
 }
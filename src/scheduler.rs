@@ -49,16 +49,36 @@ impl Scheduler {
 
     pub(crate) fn put_and_try_run(&self, runnable: Box<dyn Runnable>) {
         self.sequence.borrow_mut().push_back(runnable);
-        if self.lock.compare_and_swap(false, true, Ordering::Relaxed) == false {
-            loop {
-                let do_next = self.sequence.borrow_mut().pop_front();
-                if let Some(mut runnable) = do_next {
-                    runnable.run();
-                } else {
-                    break;
-                }
+        if self.try_lock() {
+            self.run_sequence();
+        }
+    }
+
+    /// Runs a routine immediately even if the scheduler is busy with other routine.
+    /// Routines put by it or queued before it are processed later in the sequence.
+    pub(crate) fn run_now(&self, mut runnable: Box<dyn Runnable>) {
+        let locked = self.try_lock();
+        runnable.run();
+        if locked {
+            self.run_sequence();
+        }
+    }
+
+    /// Takes the lock if the scheduler is not busy.
+    fn try_lock(&self) -> bool {
+        self.lock.compare_and_swap(false, true, Ordering::Relaxed) == false
+    }
+
+    /// Runs routines of the sequence till it's empty and releases the lock.
+    fn run_sequence(&self) {
+        loop {
+            let do_next = self.sequence.borrow_mut().pop_front();
+            if let Some(mut runnable) = do_next {
+                runnable.run();
+            } else {
+                break;
             }
-            self.lock.store(false, Ordering::Relaxed);
         }
+        self.lock.store(false, Ordering::Relaxed);
     }
 }
@@ -0,0 +1,192 @@
+//! This module contains a renderer of components to HTML strings, it's enabled
+//! with `ssr` feature. It doesn't need a browser, so it could be used by a server
+//! to send pre-rendered pages. The renderer only reads virtual nodes and never
+//! touches DOM handles of `stdweb`, but virtual nodes refer to `stdweb` types,
+//! so native binaries still have to be linked with the JS bridge of `stdweb`.
+//!
+//! Listeners aren't rendered. Components are created with `Component::create`
+//! and rendered once with `Renderable::view`; messages sent to them are ignored.
+//! A raw HTML of `VRaw` nodes is written as is, it's cleaned by their sanitizers already.
+
+use std::borrow::Cow;
+use html::{Component, ComponentLink, Renderable, Scope};
+use virtual_dom::{VNode, VTag};
+
+/// Elements which can't have children and closing tags.
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input",
+    "link", "meta", "param", "source", "track", "wbr",
+];
+
+/// Elements which content is a raw text and is not escaped.
+const RAW_TEXT_ELEMENTS: &[&str] = &["script", "style"];
+
+/// Creates a component with properties and renders it to a string.
+pub fn render_to_string<COMP>(props: COMP::Properties) -> String
+where
+    COMP: Component + Renderable<COMP>,
+{
+    let mut buf = String::new();
+    render_component::<COMP>(props, &mut buf);
+    buf
+}
+
+/// Renders a virtual tree to a string. Components of the tree
+/// are created and rendered recursively.
+pub fn render_node_to_string<COMP: Component>(node: &VNode<COMP>) -> String {
+    let mut buf = String::new();
+    render_node(node, None, false, &mut buf);
+    buf
+}
+
+/// Creates a component with a scope which is never mounted and
+/// writes its view to the buffer.
+pub(crate) fn render_component<COMP>(props: COMP::Properties, buf: &mut String)
+where
+    COMP: Component + Renderable<COMP>,
+{
+    let scope = Scope::new();
+    let link = ComponentLink::connect(&scope);
+    let mut component = COMP::create(props, link);
+    let node = component.view();
+    render_node(&node, None, false, buf);
+    component.destroy();
+}
+
+pub(crate) fn render_node<COMP: Component>(
+    node: &VNode<COMP>,
+    ns: Option<&Cow<'static, str>>,
+    raw: bool,
+    buf: &mut String,
+) {
+    match *node {
+        VNode::VTag(ref vtag) => render_tag(vtag, ns, buf),
+        VNode::VText(ref vtext) => {
+            if raw {
+                buf.push_str(&vtext.text);
+            } else {
+                escape_text(&vtext.text, buf);
+            }
+        }
+        VNode::VComp(ref vcomp) => vcomp.render(buf),
+        VNode::VRaw(ref vraw) => buf.push_str(&vraw.html),
+        VNode::VList(ref vlist) => {
+            for child in &vlist.childs {
+                render_node(child, ns, raw, buf);
+            }
+        }
+        VNode::VChild(ref vchild) => vchild.render(ns, raw, buf),
+        // A foreign node and a node of a portal are inserted when the tree is hydrated
+        VNode::VForeign(_) | VNode::VPortal(_) => {}
+        VNode::VRef(_) => {
+            warn!("VRef node can't be rendered to a string and skipped");
+        }
+    }
+}
+
+fn render_tag<COMP: Component>(
+    vtag: &VTag<COMP>,
+    ns: Option<&Cow<'static, str>>,
+    buf: &mut String,
+) {
+    let tag = vtag.tag();
+    buf.push('<');
+    buf.push_str(tag);
+    if let Some(ref tag_ns) = vtag.ns {
+        if ns != Some(tag_ns) && !vtag.attributes.contains_key("xmlns") {
+            render_attribute("xmlns", tag_ns, buf);
+        }
+    }
+    if let Some(ref kind) = vtag.kind {
+        render_attribute("type", kind, buf);
+    }
+    if !vtag.classes.is_empty() {
+        // Classes and attributes are sorted to get the same output every time
+        let mut classes = vtag.classes.iter().map(String::as_str).collect::<Vec<_>>();
+        classes.sort();
+        render_attribute("class", &classes.join(" "), buf);
+    }
+    let mut attributes = vtag.attributes.iter().collect::<Vec<_>>();
+    attributes.sort();
+    for (name, value) in attributes {
+        if name == "style" && !vtag.styles.is_empty() {
+            continue;
+        }
+        render_attribute(name, value, buf);
+    }
+    if !vtag.styles.is_empty() {
+        // Properties are added after the `style` attribute like the browser does
+        let mut declarations = vtag.attributes
+            .get("style")
+            .map(|style| style.trim().trim_end_matches(';').to_owned())
+            .into_iter()
+            .filter(|style| !style.is_empty())
+            .collect::<Vec<_>>();
+        let mut styles = vtag.styles.iter().collect::<Vec<_>>();
+        styles.sort();
+        for (name, value) in styles {
+            declarations.push(format!("{}: {}", name, value));
+        }
+        render_attribute("style", &format!("{};", declarations.join("; ")), buf);
+    }
+    let is_textarea = tag.eq_ignore_ascii_case("textarea");
+    if !is_textarea {
+        if let Some(ref value) = vtag.value {
+            render_attribute("value", value, buf);
+        }
+    }
+    if vtag.checked {
+        buf.push_str(" checked");
+    }
+    buf.push('>');
+
+    let is_html = vtag.ns.is_none();
+    if is_html && VOID_ELEMENTS.iter().any(|void| tag.eq_ignore_ascii_case(void)) {
+        return;
+    }
+    if is_textarea {
+        if let Some(ref value) = vtag.value {
+            escape_text(value, buf);
+        }
+    }
+    let raw = is_html && RAW_TEXT_ELEMENTS.iter().any(|raw| tag.eq_ignore_ascii_case(raw));
+    for child in &vtag.childs {
+        render_node(child, vtag.ns.as_ref(), raw, buf);
+    }
+    buf.push_str("</");
+    buf.push_str(tag);
+    buf.push('>');
+}
+
+fn render_attribute(name: &str, value: &str, buf: &mut String) {
+    buf.push(' ');
+    buf.push_str(name);
+    buf.push_str("=\"");
+    escape_attribute(value, buf);
+    buf.push('"');
+}
+
+/// Escapes a text of a text node.
+fn escape_text(text: &str, buf: &mut String) {
+    for c in text.chars() {
+        match c {
+            '&' => buf.push_str("&amp;"),
+            '<' => buf.push_str("&lt;"),
+            '>' => buf.push_str("&gt;"),
+            c => buf.push(c),
+        }
+    }
+}
+
+/// Escapes a value of an attribute which is enclosed in double quotes.
+fn escape_attribute(value: &str, buf: &mut String) {
+    for c in value.chars() {
+        match c {
+            '&' => buf.push_str("&amp;"),
+            '"' => buf.push_str("&quot;"),
+            '<' => buf.push_str("&lt;"),
+            '>' => buf.push_str("&gt;"),
+            c => buf.push(c),
+        }
+    }
+}
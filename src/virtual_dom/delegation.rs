@@ -0,0 +1,239 @@
+//! This module contains a registry of delegated listeners. Instead of a JS listener
+//! per element it keeps a single JS listener per event type on the mount element
+//! and dispatches events to handlers of elements found on the way from the target.
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::{Rc, Weak};
+use stdweb::{Reference, Value};
+use stdweb::unstable::{TryFrom, TryInto};
+use stdweb::web::{Element, INode, Node};
+
+/// Events which don't bubble. They are caught on the capturing phase
+/// and dispatched to the target only. Handlers of other events are
+/// called on the bubbling phase after listeners of the elements.
+const NON_BUBBLING_EVENTS: &[&str] = &[
+    "blur", "focus", "mouseenter", "mouseleave",
+    "pointerenter", "pointerleave", "scroll", "load", "error",
+];
+
+thread_local! {
+    /// Ids of elements and handlers are unique for all registries, because
+    /// an app could be mounted inside of an element of other app.
+    static NEXT_ID: Cell<u32> = Cell::new(1);
+}
+
+/// A handler which converts a JS event to the type of its listener.
+type Dispatcher = Rc<dyn Fn(Value)>;
+
+/// Delegates listeners of elements to a root element.
+#[derive(Clone)]
+pub(crate) struct Delegation {
+    registry: Rc<RefCell<Registry>>,
+}
+
+struct Registry {
+    root: Element,
+    /// Handlers of elements by ids of elements in the order they were registered.
+    /// An element could have several handlers of the same type of events.
+    handlers: HashMap<u32, Vec<Handler>>,
+    /// JS listeners installed on the root element.
+    listeners: HashMap<&'static str, Value>,
+}
+
+struct Handler {
+    id: u32,
+    event_type: &'static str,
+    dispatcher: Dispatcher,
+}
+
+impl Delegation {
+    /// Creates a registry which listens events on the `root` element.
+    pub(crate) fn new(root: Element) -> Self {
+        let registry = Registry {
+            root,
+            handlers: HashMap::new(),
+            listeners: HashMap::new(),
+        };
+        Delegation {
+            registry: Rc::new(RefCell::new(registry)),
+        }
+    }
+
+    /// Registers a handler of events of `event_type` which target the `element`
+    /// or its descendants. A listener of the root element is installed once
+    /// for every type of events.
+    pub(crate) fn register<E, F>(
+        &self,
+        element: &Element,
+        event_type: &'static str,
+        handler: F,
+    ) -> DelegatedHandle
+    where
+        E: TryFrom<Value> + 'static,
+        F: Fn(E) + 'static,
+    {
+        let element_id = element_id(element.as_ref()).unwrap_or_else(|| {
+            let id = next_id();
+            js! { @(no_return)
+                @{element.as_ref()}.__yew_delegation_id = @{id};
+            }
+            id
+        });
+        let installed = self.registry.borrow().listeners.contains_key(event_type);
+        if !installed {
+            let listener = install(&self.registry, event_type);
+            self.registry.borrow_mut().listeners.insert(event_type, listener);
+        }
+        // Listeners of different kinds could listen the same DOM event
+        // with different types, every handler converts the event itself
+        let dispatcher = move |event: Value| {
+            if let Ok(event) = E::try_from(event) {
+                handler(event);
+            }
+        };
+        let handler = Handler {
+            id: next_id(),
+            event_type,
+            dispatcher: Rc::new(dispatcher),
+        };
+        let id = handler.id;
+        self.registry
+            .borrow_mut()
+            .handlers
+            .entry(element_id)
+            .or_insert_with(Vec::new)
+            .push(handler);
+        DelegatedHandle {
+            registry: Rc::downgrade(&self.registry),
+            element_id,
+            id,
+        }
+    }
+}
+
+/// A handle of a delegated listener which removes its handler from the registry.
+pub(crate) struct DelegatedHandle {
+    registry: Weak<RefCell<Registry>>,
+    element_id: u32,
+    id: u32,
+}
+
+impl DelegatedHandle {
+    /// Removes the handler. The listener of the root element is kept for other elements.
+    pub(crate) fn remove(self) {
+        if let Some(registry) = self.registry.upgrade() {
+            let mut registry = registry.borrow_mut();
+            let empty = match registry.handlers.get_mut(&self.element_id) {
+                Some(handlers) => {
+                    handlers.retain(|handler| handler.id != self.id);
+                    handlers.is_empty()
+                }
+                None => false,
+            };
+            if empty {
+                registry.handlers.remove(&self.element_id);
+            }
+        }
+    }
+}
+
+impl Drop for Registry {
+    fn drop(&mut self) {
+        for (event_type, listener) in self.listeners.drain() {
+            let capture = is_non_bubbling(event_type);
+            js! { @(no_return)
+                var listener = @{listener};
+                @{self.root.as_ref()}.removeEventListener(@{event_type}, listener, @{capture});
+                listener.drop();
+            }
+        }
+    }
+}
+
+fn next_id() -> u32 {
+    NEXT_ID.with(|next| {
+        let id = next.get();
+        next.set(id + 1);
+        id
+    })
+}
+
+fn is_non_bubbling(event_type: &str) -> bool {
+    NON_BUBBLING_EVENTS.contains(&event_type)
+}
+
+/// Returns an id of the element assigned by a registry.
+fn element_id(reference: &Reference) -> Option<u32> {
+    let id = js!(
+        return @{reference}.__yew_delegation_id;
+    );
+    id.try_into().ok()
+}
+
+/// Installs a listener of events of `event_type` on the root element.
+fn install(registry: &Rc<RefCell<Registry>>, event_type: &'static str) -> Value {
+    let weak = Rc::downgrade(registry);
+    let listener = move |event: Value| {
+        if let Some(registry) = weak.upgrade() {
+            dispatch(&registry, event_type, event);
+        }
+    };
+    let root = registry.borrow().root.clone();
+    let capture = is_non_bubbling(event_type);
+    js!(
+        var listener = @{listener};
+        @{root.as_ref()}.addEventListener(@{event_type}, listener, @{capture});
+        return listener;
+    )
+}
+
+/// Calls handlers of elements on the way from the target to the root
+/// like the event bubbles through them. The dispatching is stopped if
+/// a handler stops propagation of the event, but the rest of handlers
+/// of the same element are called like the browser does.
+fn dispatch(registry: &Rc<RefCell<Registry>>, event_type: &'static str, event: Value) {
+    let target = js!(
+        return @{&event}.target;
+    );
+    let bubbles = !is_non_bubbling(event_type);
+    let elements = {
+        let registry = registry.borrow();
+        let root = registry.root.as_node();
+        let mut current = Node::try_from(target).ok();
+        let mut elements = Vec::new();
+        while let Some(node) = current {
+            let handlers = element_id(node.as_ref())
+                .and_then(|id| registry.handlers.get(&id))
+                .map(|handlers| {
+                    handlers
+                        .iter()
+                        .filter(|handler| handler.event_type == event_type)
+                        .map(|handler| handler.dispatcher.clone())
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+            if !handlers.is_empty() {
+                elements.push(handlers);
+            }
+            if !bubbles || node == *root {
+                break;
+            }
+            current = node.parent_node();
+        }
+        elements
+    };
+    // The registry is released before calls, because handlers
+    // could render and register or remove handlers.
+    for handlers in elements {
+        for dispatcher in handlers {
+            dispatcher(event.clone());
+        }
+        let stopped: bool = js!(
+            return @{&event}.cancelBubble;
+        ).try_into().unwrap_or(false);
+        if stopped {
+            break;
+        }
+    }
+}
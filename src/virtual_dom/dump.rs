@@ -0,0 +1,165 @@
+//! This module contains a printer of virtual trees in a HTML-like form.
+//! It's used by `Debug` of virtual nodes: `{:?}` writes a tree to a single line
+//! and `{:#?}` writes every node to a separate line with indentation:
+//!
+//! ```text
+//! <ul class="list" onclick=..>
+//!     <li>
+//!         "first"
+//!     </li>
+//!     <my_app::Item: key="2" ItemProps { label: "second" } />
+//! </ul>
+//! ```
+
+use std::fmt;
+use html::Component;
+use stdweb::web::INode;
+use super::{PropValue, VComp, VList, VNode, VPortal, VTag};
+
+const INDENT: &str = "    ";
+
+/// Writes a virtual tree to a formatter.
+pub(crate) struct Printer<'a, 'b: 'a> {
+    f: &'a mut fmt::Formatter<'b>,
+    pretty: bool,
+    depth: usize,
+    started: bool,
+}
+
+impl<'a, 'b: 'a> Printer<'a, 'b> {
+    /// Creates a printer which writes every node to a separate line
+    /// if the alternate flag of the formatter is set.
+    pub(crate) fn new(f: &'a mut fmt::Formatter<'b>) -> Self {
+        let pretty = f.alternate();
+        Printer {
+            f,
+            pretty,
+            depth: 0,
+            started: false,
+        }
+    }
+
+    /// Writes a node and its children.
+    pub(crate) fn node<COMP: Component>(&mut self, node: &VNode<COMP>) -> fmt::Result {
+        match *node {
+            VNode::VTag(ref vtag) => self.tag(vtag),
+            VNode::VText(ref vtext) => self.line(format_args!("{:?}", vtext)),
+            VNode::VComp(ref vcomp) => self.comp(vcomp),
+            VNode::VList(ref vlist) => self.list(vlist),
+            VNode::VRaw(ref vraw) => self.line(format_args!("{:?}", vraw)),
+            VNode::VForeign(ref vforeign) => self.line(format_args!("{:?}", vforeign)),
+            VNode::VPortal(ref vportal) => self.portal(vportal),
+            VNode::VChild(ref vchild) => vchild.dump(self),
+            VNode::VRef(ref node) => self.line(format_args!("VRef({})", node.node_name())),
+        }
+    }
+
+    /// Writes a tag with attributes. Attributes, classes and styles are sorted
+    /// to get the same output every time.
+    pub(crate) fn tag<COMP: Component>(&mut self, vtag: &VTag<COMP>) -> fmt::Result {
+        let mut open = format!("<{}", vtag.tag());
+        if let Some(ref key) = vtag.key {
+            open.push_str(&format!(" key={:?}", key));
+        }
+        if let Some(ref kind) = vtag.kind {
+            open.push_str(&format!(" type={:?}", kind));
+        }
+        if !vtag.classes.is_empty() {
+            let mut classes = vtag.classes.iter().map(String::as_str).collect::<Vec<_>>();
+            classes.sort();
+            open.push_str(&format!(" class={:?}", classes.join(" ")));
+        }
+        let mut attributes = vtag.attributes.iter().collect::<Vec<_>>();
+        attributes.sort();
+        for (name, value) in attributes {
+            open.push_str(&format!(" {}={:?}", name, value));
+        }
+        if !vtag.styles.is_empty() {
+            let mut styles = vtag.styles.iter().collect::<Vec<_>>();
+            styles.sort();
+            let declarations = styles
+                .into_iter()
+                .map(|(name, value)| format!("{}: {}", name, value))
+                .collect::<Vec<_>>();
+            open.push_str(&format!(" style={:?}", declarations.join("; ")));
+        }
+        if let Some(ref value) = vtag.value {
+            open.push_str(&format!(" value={:?}", value));
+        }
+        if vtag.checked {
+            open.push_str(" checked");
+        }
+        let mut props = vtag.props.iter().collect::<Vec<_>>();
+        props.sort_by(|a, b| a.0.cmp(b.0));
+        for (name, value) in props {
+            match *value {
+                PropValue::Value(ref value) => open.push_str(&format!(" prop:{}={:?}", name, value)),
+                PropValue::Json(ref json) => open.push_str(&format!(" prop:{}=Json({})", name, json)),
+            }
+        }
+        for listener in &vtag.listeners {
+            open.push_str(&format!(" {}=..", listener.kind()));
+        }
+        if vtag.childs.is_empty() {
+            return self.line(format_args!("{} />", open));
+        }
+        self.line(format_args!("{}>", open))?;
+        self.children(&vtag.childs)?;
+        self.line(format_args!("</{}>", vtag.tag()))
+    }
+
+    /// Writes a fragment with its children.
+    pub(crate) fn list<COMP: Component>(&mut self, vlist: &VList<COMP>) -> fmt::Result {
+        match vlist.key {
+            Some(ref key) => self.line(format_args!("<key={:?}>", key))?,
+            None => self.line(format_args!("<>"))?,
+        }
+        self.children(&vlist.childs)?;
+        self.line(format_args!("</>"))
+    }
+
+    /// Writes a component with a type name and properties, properties of
+    /// rendered components are taken already and they are not written.
+    pub(crate) fn comp<COMP: Component>(&mut self, vcomp: &VComp<COMP>) -> fmt::Result {
+        let mut open = format!("<{}:", vcomp.type_name());
+        if let Some(ref key) = vcomp.key {
+            open.push_str(&format!(" key={:?}", key));
+        }
+        let props = vcomp.props_to_string();
+        if !props.is_empty() {
+            open.push(' ');
+            open.push_str(&props);
+        }
+        self.line(format_args!("{} />", open))
+    }
+
+    /// Writes a portal with a name of the host.
+    pub(crate) fn portal<COMP: Component>(&mut self, vportal: &VPortal<COMP>) -> fmt::Result {
+        self.line(format_args!("<VPortal host={}>", vportal.host.node_name()))?;
+        self.children(::std::slice::from_ref(&*vportal.node))?;
+        self.line(format_args!("</VPortal>"))
+    }
+
+    fn children<COMP: Component>(&mut self, children: &[VNode<COMP>]) -> fmt::Result {
+        self.depth += 1;
+        for child in children {
+            self.node(child)?;
+        }
+        self.depth -= 1;
+        Ok(())
+    }
+
+    /// Writes a line with indentation in the pretty mode or as is.
+    fn line(&mut self, args: fmt::Arguments<'_>) -> fmt::Result {
+        if self.pretty {
+            if self.started {
+                self.f.write_str("\n")?;
+            }
+            for _ in 0..self.depth {
+                self.f.write_str(INDENT)?;
+            }
+        }
+        self.started = true;
+        self.f.write_fmt(args)
+    }
+}
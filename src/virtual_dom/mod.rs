@@ -1,21 +1,40 @@
 //! This module contains the implementation of reactive virtual dom concept.
 
+mod delegation;
+mod dump;
+pub mod vchild;
 pub mod vcomp;
+pub mod vforeign;
 pub mod vlist;
 pub mod vnode;
+pub mod vportal;
+pub mod vraw;
 pub mod vtag;
 pub mod vtext;
 
+use std::any::Any;
 use std::collections::{HashMap, HashSet};
 use std::fmt;
+use std::rc::Rc;
+use stdweb::Value;
+use stdweb::unstable::TryFrom;
 use stdweb::web::{Element, EventListenerHandle, Node};
+use stdweb::web::event::IEvent;
 
+pub use self::vchild::VChild;
 pub use self::vcomp::VComp;
+pub use self::vforeign::VForeign;
 pub use self::vlist::VList;
 pub use self::vnode::VNode;
+pub use self::vportal::VPortal;
+pub use self::vraw::VRaw;
 pub use self::vtag::VTag;
 pub use self::vtext::VText;
+pub(crate) use self::delegation::{DelegatedHandle, Delegation};
 use html::{Component, Scope};
+use format::Json;
+use serde::Serialize;
+use serde_json;
 
 /// `Listener` trait is an universal implementation of an event listener
 /// which helps to bind Rust-listener to JS-listener (DOM).
@@ -24,7 +43,174 @@ pub trait Listener<COMP: Component> {
     fn kind(&self) -> &'static str;
     /// Attaches listener to the element and uses scope instance to send
     /// prepaired event back to the yew main loop.
-    fn attach(&mut self, element: &Element, scope: Scope<COMP>) -> EventListenerHandle;
+    fn attach(&mut self, element: &Element, scope: Scope<COMP>) -> AttachedListener;
+    /// Puts the handler of this listener to the slot of a listener of
+    /// the same kind which was attached before, so the JS listener is kept.
+    /// Returns `false` if the attached listener can't take the handler.
+    fn reattach(&mut self, attached: &AttachedListener) -> bool;
+}
+
+/// Options of a listener which are set with modifiers in `html!` template:
+/// `<form onsubmit.prevent=|_| Msg::Submit,>`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ListenerOptions {
+    /// Calls `preventDefault` before the handler.
+    pub prevent_default: bool,
+    /// Calls `stopPropagation` before the handler.
+    pub stop_propagation: bool,
+    /// Adds the listener as passive. Browsers ignore `preventDefault` calls of passive listeners.
+    pub passive: bool,
+    /// Adds the listener for the capturing phase.
+    pub capture: bool,
+}
+
+impl ListenerOptions {
+    /// Sets `prevent_default` option (`.prevent` modifier).
+    pub fn prevent(mut self) -> Self {
+        self.prevent_default = true;
+        self
+    }
+
+    /// Sets `stop_propagation` option (`.stop` modifier).
+    pub fn stop(mut self) -> Self {
+        self.stop_propagation = true;
+        self
+    }
+
+    /// Sets `passive` option (`.passive` modifier).
+    pub fn passive(mut self) -> Self {
+        self.passive = true;
+        self
+    }
+
+    /// Sets `capture` option (`.capture` modifier).
+    pub fn capture(mut self) -> Self {
+        self.capture = true;
+        self
+    }
+
+    /// Returns `true` if the listener has to be added to the element itself
+    /// instead of a root element which delegates events.
+    fn is_native(&self) -> bool {
+        self.passive || self.capture
+    }
+}
+
+/// A listener attached to an element. It keeps the handle of JS listener
+/// and a slot with a handler which the JS listener calls.
+pub struct AttachedListener {
+    kind: &'static str,
+    options: ListenerOptions,
+    handle: ListenerHandle,
+    slot: Rc<dyn Any>,
+}
+
+/// A JS listener of an element or a handler delegated to a root element.
+enum ListenerHandle {
+    Direct(EventListenerHandle),
+    Native(NativeHandle),
+    Delegated(DelegatedHandle),
+}
+
+/// A JS listener added with options which `EventListenerHandle` doesn't support.
+/// It could listen events of any type, not only types known by `stdweb`.
+struct NativeHandle {
+    element: Element,
+    event_type: &'static str,
+    capture: bool,
+    listener: Value,
+}
+
+impl NativeHandle {
+    fn remove(self) {
+        js! { @(no_return)
+            var listener = @{self.listener};
+            @{self.element.as_ref()}.removeEventListener(@{self.event_type}, listener, @{self.capture});
+            listener.drop();
+        }
+    }
+}
+
+impl AttachedListener {
+    /// Creates a new instance with a handle of JS listener and
+    /// a slot which the listener takes a handler from.
+    pub fn new(kind: &'static str, handle: EventListenerHandle, slot: Rc<dyn Any>) -> Self {
+        AttachedListener {
+            kind,
+            options: ListenerOptions::default(),
+            handle: ListenerHandle::Direct(handle),
+            slot,
+        }
+    }
+
+    /// Adds a JS listener of events of `event_type` to the element or registers it
+    /// in a registry of delegated listeners if it's given. Passive and capturing
+    /// listeners are always added to the element.
+    pub(crate) fn listen<E, F>(
+        kind: &'static str,
+        event_type: &'static str,
+        element: &Element,
+        options: ListenerOptions,
+        delegation: Option<&Delegation>,
+        listener: F,
+        slot: Rc<dyn Any>,
+    ) -> Self
+    where
+        E: IEvent + TryFrom<Value> + Clone + 'static,
+        F: Fn(E) + 'static,
+    {
+        let handle = match delegation {
+            Some(delegation) if !options.is_native() => {
+                ListenerHandle::Delegated(delegation.register(element, event_type, listener))
+            }
+            _ => {
+                let listener = js!(
+                    var listener = @{listener};
+                    @{element.as_ref()}.addEventListener(@{event_type}, listener, {
+                        capture: @{options.capture},
+                        passive: @{options.passive}
+                    });
+                    return listener;
+                );
+                ListenerHandle::Native(NativeHandle {
+                    element: element.clone(),
+                    event_type,
+                    capture: options.capture,
+                    listener,
+                })
+            }
+        };
+        AttachedListener {
+            kind,
+            options,
+            handle,
+            slot,
+        }
+    }
+
+    /// Returns standard name of DOM's event.
+    pub fn kind(&self) -> &'static str {
+        self.kind
+    }
+
+    /// Returns the options the listener was attached with.
+    pub fn options(&self) -> ListenerOptions {
+        self.options
+    }
+
+    /// Returns the slot of the handler if it has the expected type.
+    pub fn slot<T: Any>(&self) -> Option<&T> {
+        self.slot.downcast_ref()
+    }
+
+    /// Removes the listener from the element.
+    pub fn remove(self) {
+        match self.handle {
+            ListenerHandle::Direct(handle) => handle.remove(),
+            ListenerHandle::Native(handle) => handle.remove(),
+            ListenerHandle::Delegated(handle) => handle.remove(),
+        }
+    }
 }
 
 impl<COMP: Component> fmt::Debug for dyn Listener<COMP> {
@@ -42,6 +228,38 @@ type Attributes = HashMap<String, String>;
 /// A set of classes.
 type Classes = HashSet<String>;
 
+/// A map of properties of an inline style.
+type Styles = HashMap<String, String>;
+
+/// A map of DOM properties.
+type Props = HashMap<String, PropValue>;
+
+/// A value of a DOM property of an element. It's set with `prop:name=value` in
+/// `html!` template: `<input type="checkbox", prop:indeterminate=true,/>`.
+/// Any value which could be converted to a JS `Value` is set as is. Wrap a serializable
+/// value with `Json` to pass it as a JS object: `<data-grid prop:rows=Json(&self.rows),>`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PropValue {
+    /// A value which is passed to JS as is.
+    Value(Value),
+    /// A JSON representation of a value which is parsed with `JSON.parse`
+    /// every time the property is set.
+    Json(String),
+}
+
+impl<T: Into<Value>> From<T> for PropValue {
+    fn from(value: T) -> Self {
+        PropValue::Value(value.into())
+    }
+}
+
+impl<T: Serialize> From<Json<T>> for PropValue {
+    fn from(Json(value): Json<T>) -> Self {
+        let json = serde_json::to_string(&value).expect("can't serialize a value of a property");
+        PropValue::Json(json)
+    }
+}
+
 /// Patch for DOM node modification.
 enum Patch<ID, T> {
     Add(ID, T),
@@ -66,10 +284,6 @@ enum Reform {
     Before(Option<Node>),
 }
 
-// TODO What about to implement `VDiff` for `Element`?
-// In makes possible to include ANY element into the tree.
-// `Ace` editor embedding for example?
-
 /// This trait provides features to update a tree by other tree comparsion.
 pub trait VDiff {
     /// The component which this instance put into.
@@ -106,4 +320,29 @@ pub trait VDiff {
         ancestor: Option<VNode<Self::Component>>,
         scope: &Scope<Self::Component>,
     ) -> Option<Node>;
+
+    /// Binds the node to existing DOM nodes instead of creating them.
+    ///
+    /// It's used to adopt nodes rendered before (on a server).
+    /// Parameters:
+    /// - `parent`: the parent node in the DOM.
+    /// - `cursor`: the next DOM node to bind. The method moves it to the node
+    ///   which follows the nodes it took.
+    /// - `scope`: the `Scope` to attach listeners to.
+    ///
+    /// Returns the last node like `apply` does. Mismatched DOM nodes are replaced.
+    fn hydrate(
+        &mut self,
+        parent: &Node,
+        cursor: &mut Option<Node>,
+        scope: &Scope<Self::Component>,
+    ) -> Option<Node>;
+}
+
+/// Reports a difference between a virtual tree and DOM nodes found
+/// during hydration. It's reported in debug builds only.
+fn hydration_mismatch(args: fmt::Arguments<'_>) {
+    if cfg!(debug_assertions) {
+        warn!("hydration mismatch: {}", args);
+    }
 }
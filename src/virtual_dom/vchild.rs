@@ -0,0 +1,341 @@
+//! This module contains the implementation of `VChild`, a node of a parent
+//! component which is rendered by a child component.
+
+use std::any::{Any, TypeId};
+#[cfg(feature = "ssr")]
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::fmt;
+use std::mem;
+use std::rc::Rc;
+use stdweb::web::{document, INode, Node};
+use html::{Component, NodeBound, Scope};
+#[cfg(feature = "ssr")]
+use ssr;
+use super::{dump, VDiff, VNode};
+use super::vcomp::LazyActivator;
+
+/// A node which a parent component passes to a child with `children` property.
+/// It's created by the view of the parent, so it uses the fields of the parent
+/// and its listeners send messages to the parent. The child only places it:
+/// clones of the node share the same DOM nodes, so a node which is rendered
+/// twice is moved to the last place.
+///
+/// When the child stops rendering the node, its DOM nodes are removed, but its
+/// listeners and components are kept till the parent renders new children.
+#[derive(Clone)]
+pub struct VChild {
+    key: Option<String>,
+    node: Rc<RefCell<dyn ParentNode>>,
+}
+
+impl VChild {
+    /// Creates a child of a node of the parent. The scope of the parent is set
+    /// to the activator when the parent renders the component which gets it.
+    pub(crate) fn new<COMP: Component>(node: VNode<COMP>, activator: LazyActivator<COMP>) -> Self {
+        let key = node.key().map(String::from);
+        let node = NodeOf {
+            state: State::Created(node),
+            activator,
+        };
+        VChild {
+            key,
+            node: Rc::new(RefCell::new(node)),
+        }
+    }
+
+    /// Returns the key of the node of the parent.
+    pub fn key(&self) -> Option<&str> {
+        self.key.as_deref()
+    }
+
+    /// Returns the first DOM node of the node.
+    pub(crate) fn first_node(&self) -> Option<Node> {
+        self.node.borrow().first_node()
+    }
+
+    /// Returns the first or the last bound of DOM nodes of the node.
+    pub(crate) fn bound(&self, first: bool) -> Option<NodeBound> {
+        self.node.borrow().bound(first)
+    }
+
+    /// Releases listeners and components of the node, it can't be rendered after that.
+    pub(crate) fn release(&mut self) {
+        self.node.borrow_mut().release();
+    }
+
+    /// Moves DOM nodes of the node before the `next` sibling.
+    pub(crate) fn move_before(&self, parent: &Node, next: Option<&Node>) {
+        self.node.borrow().move_before(parent, next);
+    }
+
+    /// Returns `true` if the node has listeners.
+    pub(crate) fn has_listeners(&self) -> bool {
+        self.node.borrow().has_listeners()
+    }
+
+    /// Removes DOM nodes of the node, but keeps it rendered.
+    pub(crate) fn detach(&mut self, parent: &Node) -> Option<Node> {
+        self.node.borrow_mut().detach(parent)
+    }
+
+    /// Renders the node in place of an ancestor. If the ancestor is a node of other
+    /// children of the same parent, they are diffed, so DOM nodes are reused.
+    pub(crate) fn apply<COMP: Component>(
+        &mut self,
+        parent: &Node,
+        precursor: Option<&Node>,
+        ancestor: Option<VNode<COMP>>,
+    ) -> Option<Node> {
+        let parent_type = self.node.borrow().parent_type();
+        let mut previous = None;
+        let next = match ancestor {
+            Some(VNode::VChild(ref vchild)) if Rc::ptr_eq(&self.node, &vchild.node) => {
+                // The node is in place already
+                return self.node.borrow().last_node().or_else(|| precursor.cloned());
+            }
+            Some(VNode::VChild(ref vchild)) if vchild.node.borrow().parent_type() == parent_type => {
+                previous = vchild.node.borrow_mut().take();
+                None
+            }
+            Some(mut vnode) => vnode.detach(parent),
+            None => precursor.and_then(INode::next_sibling),
+        };
+        let last = self.node.borrow_mut().apply(parent, next, previous);
+        last.or_else(|| precursor.cloned())
+    }
+
+    /// Renders the node over existing DOM nodes.
+    pub(crate) fn hydrate(&mut self, parent: &Node, cursor: &mut Option<Node>) -> Option<Node> {
+        self.node.borrow_mut().hydrate(parent, cursor)
+    }
+
+    /// Renders the node to a string.
+    #[cfg(feature = "ssr")]
+    pub(crate) fn render(&self, ns: Option<&Cow<'static, str>>, raw: bool, buf: &mut String) {
+        self.node.borrow().render(ns, raw, buf);
+    }
+
+    /// Writes the node of the parent to a dump of a tree.
+    pub(crate) fn dump(&self, printer: &mut dump::Printer<'_, '_>) -> fmt::Result {
+        self.node.borrow().dump(printer)
+    }
+}
+
+impl fmt::Debug for VChild {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.dump(&mut dump::Printer::new(f))
+    }
+}
+
+/// Nodes are equal if they are clones or if they are equal nodes of the same parent
+/// without listeners. Handlers of listeners can't be compared, so the nodes with
+/// listeners always differ: the child gets them and renders the new handlers.
+impl PartialEq for VChild {
+    fn eq(&self, other: &VChild) -> bool {
+        Rc::ptr_eq(&self.node, &other.node) || self.node.borrow().eq(&*other.node.borrow())
+    }
+}
+
+/// A node of a parent with a hidden type of the parent.
+trait ParentNode {
+    fn as_any(&self) -> &dyn Any;
+    fn parent_type(&self) -> TypeId;
+    /// Takes the rendered node, so new children of the parent are diffed with it.
+    fn take(&mut self) -> Option<Box<dyn Any>>;
+    fn apply(&mut self, parent: &Node, next: Option<Node>, previous: Option<Box<dyn Any>>) -> Option<Node>;
+    fn hydrate(&mut self, parent: &Node, cursor: &mut Option<Node>) -> Option<Node>;
+    fn detach(&mut self, parent: &Node) -> Option<Node>;
+    fn release(&mut self);
+    fn first_node(&self) -> Option<Node>;
+    fn last_node(&self) -> Option<Node>;
+    fn bound(&self, first: bool) -> Option<NodeBound>;
+    fn move_before(&self, parent: &Node, next: Option<&Node>);
+    fn has_listeners(&self) -> bool;
+    fn eq(&self, other: &dyn ParentNode) -> bool;
+    fn dump(&self, printer: &mut dump::Printer<'_, '_>) -> fmt::Result;
+    #[cfg(feature = "ssr")]
+    fn render(&self, ns: Option<&Cow<'static, str>>, raw: bool, buf: &mut String);
+}
+
+enum State<COMP: Component> {
+    /// The node was created by the parent and was not rendered yet.
+    Created(VNode<COMP>),
+    /// The node was rendered, its DOM nodes are removed if the child detached it.
+    Rendered(VNode<COMP>),
+    /// The node was taken by new children or released.
+    Taken,
+}
+
+struct NodeOf<COMP: Component> {
+    state: State<COMP>,
+    activator: LazyActivator<COMP>,
+}
+
+impl<COMP: Component> NodeOf<COMP> {
+    fn node(&self) -> Option<&VNode<COMP>> {
+        match self.state {
+            State::Created(ref node) | State::Rendered(ref node) => Some(node),
+            State::Taken => None,
+        }
+    }
+
+    fn scope(&self) -> Scope<COMP> {
+        self.activator
+            .borrow()
+            .clone()
+            .expect("children were not activated by the parent component")
+    }
+}
+
+impl<COMP: Component> ParentNode for NodeOf<COMP> {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn parent_type(&self) -> TypeId {
+        TypeId::of::<COMP>()
+    }
+
+    fn take(&mut self) -> Option<Box<dyn Any>> {
+        match mem::replace(&mut self.state, State::Taken) {
+            State::Rendered(node) => Some(Box::new(node)),
+            state => {
+                self.state = state;
+                None
+            }
+        }
+    }
+
+    fn apply(&mut self, parent: &Node, next: Option<Node>, previous: Option<Box<dyn Any>>) -> Option<Node> {
+        let previous = previous
+            .and_then(|previous| previous.downcast::<VNode<COMP>>().ok())
+            .map(|previous| *previous);
+        match mem::replace(&mut self.state, State::Taken) {
+            State::Created(mut node) => {
+                // The node replaces the previous children or a placeholder which stakes out its place
+                let ancestor = previous.unwrap_or_else(|| {
+                    let placeholder = document().create_text_node("");
+                    match next {
+                        Some(ref next) => {
+                            parent
+                                .insert_before(&placeholder, next)
+                                .expect("can't insert placeholder of children");
+                        }
+                        None => parent.append_child(&placeholder),
+                    }
+                    VNode::VRef(placeholder.as_node().to_owned())
+                });
+                let last = node.apply(parent, None, Some(ancestor), &self.scope());
+                self.state = State::Rendered(node);
+                last
+            }
+            State::Rendered(node) => {
+                let next = match previous {
+                    Some(mut previous) => previous.detach(parent),
+                    None => next,
+                };
+                node.move_before(parent, next.as_ref());
+                self.state = State::Rendered(node);
+                self.last_node()
+            }
+            State::Taken => {
+                warn!("children were released or replaced by new children and can't be rendered");
+                None
+            }
+        }
+    }
+
+    fn hydrate(&mut self, parent: &Node, cursor: &mut Option<Node>) -> Option<Node> {
+        match mem::replace(&mut self.state, State::Taken) {
+            State::Created(mut node) => {
+                let last = node.hydrate(parent, cursor, &self.scope());
+                self.state = State::Rendered(node);
+                last
+            }
+            state => {
+                self.state = state;
+                self.apply(parent, cursor.clone(), None)
+            }
+        }
+    }
+
+    fn detach(&mut self, parent: &Node) -> Option<Node> {
+        let bounds = match self.state {
+            State::Rendered(ref node) => node.bounds(),
+            _ => None,
+        };
+        let (first, last) = match bounds.map(|bounds| (bounds.first(), bounds.last())) {
+            Some((Some(first), Some(last))) => (first, last),
+            _ => return None,
+        };
+        // Listeners and components are kept, because the child could render the node again
+        let sibling = last.next_sibling();
+        let mut current = Some(first);
+        while let Some(node) = current {
+            current = if node == last { None } else { node.next_sibling() };
+            if parent.remove_child(&node).is_err() {
+                warn!("Node not found to remove VChild");
+            }
+        }
+        sibling
+    }
+
+    fn release(&mut self) {
+        if let State::Rendered(mut node) = mem::replace(&mut self.state, State::Taken) {
+            node.release();
+        }
+    }
+
+    fn first_node(&self) -> Option<Node> {
+        self.node().and_then(VNode::first_node)
+    }
+
+    fn last_node(&self) -> Option<Node> {
+        self.node().and_then(VNode::bounds).and_then(|bounds| bounds.last())
+    }
+
+    fn bound(&self, first: bool) -> Option<NodeBound> {
+        self.node().and_then(|node| node.bound(first))
+    }
+
+    fn move_before(&self, parent: &Node, next: Option<&Node>) {
+        if let State::Rendered(ref node) = self.state {
+            node.move_before(parent, next);
+        }
+    }
+
+    fn has_listeners(&self) -> bool {
+        self.node().map_or(false, VNode::has_listeners)
+    }
+
+    fn eq(&self, other: &dyn ParentNode) -> bool {
+        let other = other.as_any().downcast_ref::<Self>().and_then(Self::node);
+        match (self.node(), other) {
+            (Some(a), Some(b)) => !a.has_listeners() && !b.has_listeners() && a == b,
+            _ => false,
+        }
+    }
+
+    fn dump(&self, printer: &mut dump::Printer<'_, '_>) -> fmt::Result {
+        match self.node() {
+            Some(node) => printer.node(node),
+            None => Ok(()),
+        }
+    }
+
+    #[cfg(feature = "ssr")]
+    fn render(&self, ns: Option<&Cow<'static, str>>, raw: bool, buf: &mut String) {
+        if let Some(node) = self.node() {
+            ssr::render_node(node, ns, raw, buf);
+        }
+    }
+}
+
+/// The listeners and components of a node which the child stopped to render
+/// are released when the last clone of it is dropped.
+impl<COMP: Component> Drop for NodeOf<COMP> {
+    fn drop(&mut self) {
+        self.release();
+    }
+}
@@ -2,39 +2,74 @@
 
 use std::any::TypeId;
 use std::cell::RefCell;
+use std::fmt;
 use std::marker::PhantomData;
+use std::mem;
 use std::rc::Rc;
 use stdweb::unstable::TryInto;
 use stdweb::web::{document, Element, INode, Node};
-use html::{Component, ComponentUpdate, Scope, NodeCell, Renderable};
+use html::{Children, Component, ComponentUpdate, Properties, Scope, NodeBound, NodeBounds, NodeCell, Renderable};
 use callback::Callback;
-use super::{Reform, VDiff, VNode};
+#[cfg(feature = "ssr")]
+use ssr;
+use super::{dump, Delegation, Reform, VChild, VDiff, VNode};
 
 struct Hidden;
 
 type AnyProps = (TypeId, *mut Hidden);
 
 /// The method generates an instance of a (child) component.
-type Generator = dyn FnMut(Element, Node, AnyProps);
+type Generator = dyn FnMut(Element, Node, AnyProps, Option<Delegation>);
+
+/// The method generates an instance of a (child) component over existing DOM nodes.
+type Hydrator = dyn FnMut(Element, Option<Node>, AnyProps, Option<Delegation>);
+
+/// The method renders an instance of a (child) component to a string.
+#[cfg(feature = "ssr")]
+type Renderer = dyn Fn(AnyProps, &mut String);
+
+/// The method compares properties of two instances of a (child) component.
+type Comparator = dyn Fn(AnyProps, AnyProps) -> bool;
+
+/// The method clones properties of a (child) component.
+type Cloner = dyn Fn(AnyProps) -> AnyProps;
+
+/// The method drops properties of a (child) component.
+type Dropper = dyn Fn(AnyProps);
+
+/// The method writes properties of a (child) component to a string for a dump of a tree.
+type Debugger = dyn Fn(AnyProps) -> String;
 
 /// A reference to unknown activator which will be attached later with a generator function.
-type LazyActivator<COMP> = Rc<RefCell<Option<Scope<COMP>>>>;
+pub(crate) type LazyActivator<COMP> = Rc<RefCell<Option<Scope<COMP>>>>;
 
 /// A virtual component.
 pub struct VComp<COMP: Component> {
+    /// A key of the component which used to match it with
+    /// the same component of the previous render.
+    pub key: Option<String>,
     type_id: TypeId,
+    type_name: &'static str,
     cell: NodeCell,
     props: Option<(TypeId, *mut Hidden)>,
     blind_sender: Box<dyn FnMut(AnyProps)>,
     generator: Box<Generator>,
+    hydrator: Box<Hydrator>,
+    #[cfg(feature = "ssr")]
+    renderer: Box<Renderer>,
+    comparator: Box<Comparator>,
+    cloner: Box<Cloner>,
+    dropper: Box<Dropper>,
+    debugger: Box<Debugger>,
     activators: Vec<LazyActivator<COMP>>,
-    destroyer: Box<dyn Fn()>,
+    destroyer: Box<dyn Fn(bool)>,
     _parent: PhantomData<COMP>,
 }
 
 impl<COMP: Component> VComp<COMP> {
     /// This method prepares a generator to make a new instance of the `Component`.
-    pub fn lazy<CHILD>() -> (CHILD::Properties, Self)
+    /// It returns a builder of properties which are attached with `set_props` later.
+    pub fn lazy<CHILD>() -> (<CHILD::Properties as Properties>::Builder, Self)
     where
         CHILD: Component + Renderable<CHILD>,
     {
@@ -44,7 +79,7 @@ impl<COMP: Component> VComp<COMP> {
         // This function creates and mounts a new component instance
         let generator = {
             let lazy_activator = lazy_activator.clone();
-            move |element, ancestor: Node, (type_id, raw): AnyProps| {
+            move |element, ancestor: Node, (type_id, raw): AnyProps, delegation| {
                 if type_id != TypeId::of::<CHILD>() {
                     panic!("tried to unpack properties of the other component");
                 }
@@ -52,18 +87,98 @@ impl<COMP: Component> VComp<COMP> {
                     let raw: *mut CHILD::Properties = ::std::mem::transmute(raw);
                     *Box::from_raw(raw)
                 };
-                let scope: Scope<CHILD> = Scope::new();
+                let scope: Scope<CHILD> = Scope::new().with_delegation(delegation);
                 let env = scope.clone();
                 *lazy_activator.borrow_mut() = Some(env);
                 scope.mount_in_place(
                     element,
                     Some(VNode::VRef(ancestor)),
                     Some(occupied.clone()),
-                    Some(props),
+                    props,
                 );
                 // TODO Consider to send ComponentUpdate::Create after `mount_in_place` call
             }
         };
+        // This function creates and mounts a new component instance over existing nodes
+        let hydrator = {
+            let lazy_activator = lazy_activator.clone();
+            let occupied = cell.clone();
+            move |element, cursor: Option<Node>, (type_id, raw): AnyProps, delegation| {
+                if type_id != TypeId::of::<CHILD>() {
+                    panic!("tried to unpack properties of the other component");
+                }
+                let props = unsafe {
+                    let raw: *mut CHILD::Properties = ::std::mem::transmute(raw);
+                    *Box::from_raw(raw)
+                };
+                let scope: Scope<CHILD> = Scope::new().with_delegation(delegation);
+                let env = scope.clone();
+                *lazy_activator.borrow_mut() = Some(env);
+                scope.hydrate_in_place(
+                    element,
+                    cursor,
+                    Some(occupied.clone()),
+                    props,
+                );
+            }
+        };
+        // This function creates an instance and renders it to a string
+        #[cfg(feature = "ssr")]
+        let renderer = move |(type_id, raw): AnyProps, buf: &mut String| {
+            if type_id != TypeId::of::<CHILD>() {
+                panic!("tried to render properties of the other component");
+            }
+            // Properties are cloned, because rendering doesn't consume the node
+            let props = unsafe {
+                let raw: *mut CHILD::Properties = ::std::mem::transmute(raw);
+                (*raw).clone()
+            };
+            ssr::render_component::<CHILD>(props, buf);
+        };
+        // This function compares properties with `PartialEq` of the properties
+        let comparator = move |(type_a, raw_a): AnyProps, (type_b, raw_b): AnyProps| {
+            if type_a != TypeId::of::<CHILD>() || type_b != TypeId::of::<CHILD>() {
+                panic!("tried to compare properties of the other component");
+            }
+            unsafe {
+                let raw_a: *mut CHILD::Properties = ::std::mem::transmute(raw_a);
+                let raw_b: *mut CHILD::Properties = ::std::mem::transmute(raw_b);
+                *raw_a == *raw_b
+            }
+        };
+        // This function clones properties which are sent to the component
+        let cloner = move |(type_id, raw): AnyProps| {
+            if type_id != TypeId::of::<CHILD>() {
+                panic!("tried to clone properties of the other component");
+            }
+            let props = unsafe {
+                let raw: *mut CHILD::Properties = ::std::mem::transmute(raw);
+                (*raw).clone()
+            };
+            let boxed = Box::into_raw(Box::new(props));
+            (type_id, boxed as *mut Hidden)
+        };
+        // This function drops properties kept by the node
+        let dropper = move |(type_id, raw): AnyProps| {
+            if type_id != TypeId::of::<CHILD>() {
+                panic!("tried to drop properties of the other component");
+            }
+            unsafe {
+                let raw: *mut CHILD::Properties = ::std::mem::transmute(raw);
+                drop(Box::from_raw(raw));
+            }
+        };
+        // This function writes properties with `Properties::fmt_debug`
+        let debugger = move |(type_id, raw): AnyProps| {
+            if type_id != TypeId::of::<CHILD>() {
+                panic!("tried to write properties of the other component");
+            }
+            let props = unsafe {
+                let raw: *mut CHILD::Properties = ::std::mem::transmute(raw);
+                &*raw
+            };
+            PropsDump(props).to_string()
+        };
         let blind_sender = {
             let mut previous_props = None;
             let lazy_activator = lazy_activator.clone();
@@ -89,52 +204,114 @@ impl<COMP: Component> VComp<COMP> {
         };
         let destroyer = {
             let lazy_activator = lazy_activator;
-            move || {
+            move |detach| {
                 lazy_activator.borrow_mut()
                     .as_mut()
                     .expect("activator for child scope was not set (destroyer)")
-                    .send(ComponentUpdate::Destroy);
+                    .destroy(detach);
             }
         };
-        let properties = Default::default();
+        let builder = <CHILD::Properties as Properties>::builder();
         let comp = VComp {
+            key: None,
             type_id: TypeId::of::<CHILD>(),
+            type_name: ::std::any::type_name::<CHILD>(),
             cell,
             props: None,
             blind_sender: Box::new(blind_sender),
             generator: Box::new(generator),
+            hydrator: Box::new(hydrator),
+            #[cfg(feature = "ssr")]
+            renderer: Box::new(renderer),
+            comparator: Box::new(comparator),
+            cloner: Box::new(cloner),
+            dropper: Box::new(dropper),
+            debugger: Box::new(debugger),
             activators: Vec::new(),
             destroyer: Box::new(destroyer),
             _parent: PhantomData,
         };
-        (properties, comp)
+        (builder, comp)
     }
 
     /// Attach properties associated with the component.
     pub fn set_props<T>(&mut self, props: T) {
         let boxed = Box::into_raw(Box::new(props));
         let data = unsafe { ::std::mem::transmute(boxed) };
-        self.props = Some((self.type_id, data));
+        if let Some(previous) = self.props.replace((self.type_id, data)) {
+            (self.dropper)(previous);
+        }
     }
 
     /// This method attach sender to a listeners, because created properties
-    /// know nothing about a parent.
+    /// know nothing about a parent. The component gets a copy of the properties,
+    /// the node keeps them to be compared with the nodes of next renders.
     fn activate_props(&mut self, sender: &Scope<COMP>) -> AnyProps {
         for activator in &self.activators {
             *activator.borrow_mut() = Some(sender.clone());
         }
-        self.props
-            .take()
-            .expect("tried to activate properties twice")
+        let props = self.props.expect("properties of the component were not set");
+        (self.cloner)(props)
+    }
+
+    /// Sets a key which used to match the component with the same
+    /// component of the previous render.
+    pub fn set_key<T: ToString>(&mut self, key: &T) {
+        self.key = Some(key.to_string());
+    }
+
+    /// Returns the first node the component was rendered to.
+    pub(crate) fn first_node(&self) -> Option<Node> {
+        self.cell.borrow().as_ref().and_then(NodeBounds::first)
+    }
+
+    /// Returns the last node the component was rendered to.
+    pub(crate) fn last_node(&self) -> Option<Node> {
+        self.cell.borrow().as_ref().and_then(NodeBounds::last)
+    }
+
+    /// Returns a bound which refers to the nodes of the component.
+    pub(crate) fn bound(&self) -> NodeBound {
+        NodeBound::Cell(self.cell.clone())
+    }
+
+    /// Destroys the component which nodes were removed with a parent element.
+    pub(crate) fn release(&mut self) {
+        (self.destroyer)(false);
+    }
+
+    /// Renders the component with attached properties to a string.
+    #[cfg(feature = "ssr")]
+    pub(crate) fn render(&self, buf: &mut String) {
+        let props = self.props.expect("properties of the component were not set");
+        (self.renderer)(props, buf);
+    }
+
+    /// Returns the type name of the component.
+    pub(crate) fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Writes properties to a string, it's empty if properties were not set.
+    pub(crate) fn props_to_string(&self) -> String {
+        self.props.map(|props| (self.debugger)(props)).unwrap_or_default()
     }
 
     /// This methods gives sender from older node.
-    pub(crate) fn grab_sender_of(&mut self, other: Self) {
+    pub(crate) fn grab_sender_of(&mut self, mut other: Self) {
         assert_eq!(self.type_id, other.type_id);
         // Grab a sender and a cell (element's reference) to reuse it later
-        self.cell = other.cell;
-        self.blind_sender = other.blind_sender;
-        self.destroyer = other.destroyer;
+        mem::swap(&mut self.cell, &mut other.cell);
+        mem::swap(&mut self.blind_sender, &mut other.blind_sender);
+        mem::swap(&mut self.destroyer, &mut other.destroyer);
+    }
+}
+
+impl<COMP: Component> Drop for VComp<COMP> {
+    fn drop(&mut self) {
+        if let Some(props) = self.props.take() {
+            (self.dropper)(props);
+        }
     }
 }
 
@@ -194,6 +371,21 @@ where
     }
 }
 
+impl<COMP> Transformer<COMP, Vec<VNode<COMP>>, Children> for VComp<COMP>
+where
+    COMP: Component,
+{
+    fn transform(&mut self, from: Vec<VNode<COMP>>) -> Children {
+        let cell = Rc::new(RefCell::new(None));
+        self.activators.push(cell.clone());
+        let nodes = from
+            .into_iter()
+            .map(|node| VChild::new(node, cell.clone()))
+            .collect();
+        Children::new(nodes)
+    }
+}
+
 impl<COMP> VComp<COMP>
 where
     COMP: Component + 'static,
@@ -204,6 +396,7 @@ where
         parent: &T,
         ancestor: Node, // Any dummy expected
         props: AnyProps,
+        env: &Scope<COMP>,
     ) {
         let element: Element = parent
             .as_node()
@@ -211,7 +404,7 @@ where
             .to_owned()
             .try_into()
             .expect("element expected to mount VComp");
-        (self.generator)(element, ancestor, props);
+        (self.generator)(element, ancestor, props, env.delegation().cloned());
     }
 
     fn send_props(&mut self, props: AnyProps) {
@@ -226,18 +419,13 @@ where
     type Component = COMP;
 
     /// Remove VComp from parent.
-    fn detach(&mut self, parent: &Node) -> Option<Node> {
+    fn detach(&mut self, _: &Node) -> Option<Node> {
+        // Keep the sibling, because the component removes its nodes itself.
+        let sibling = self.last_node().and_then(|node| node.next_sibling());
         // Destroy the loop. It's impossible to use `Drop`,
         // because parts can be reused with `grab_sender_of`.
-        (self.destroyer)(); // TODO Chech it works
-        // Keep the sibling in the cell and send a message `Drop` to a loop
-        self.cell.borrow_mut().take().and_then(|node| {
-            let sibling = node.next_sibling();
-            parent
-                .remove_child(&node)
-                .expect("can't remove the component");
-            sibling
-        })
+        (self.destroyer)(true);
+        sibling
     }
 
     /// Renders independent component over DOM `Element`.
@@ -295,15 +483,64 @@ where
                     }
                 }
                 let node = element.as_node().to_owned();
-                self.mount(parent, node, any_props);
+                // The component replaces the dummy node when it will be created,
+                // but till then siblings have to be placed after the dummy.
+                let dummy = NodeBounds::new(NodeBound::Node(node.clone()), NodeBound::Node(node.clone()));
+                *self.cell.borrow_mut() = Some(dummy);
+                self.mount(parent, node, any_props, env);
             }
         }
-        self.cell.borrow().as_ref().map(|node| node.to_owned())
+        self.last_node()
+    }
+
+    /// Creates the component over existing DOM nodes. The cursor
+    /// is moved to the node which follows the last node of the component.
+    fn hydrate(
+        &mut self,
+        parent: &Node,
+        cursor: &mut Option<Node>,
+        env: &Scope<Self::Component>,
+    ) -> Option<Node> {
+        let any_props = self.activate_props(env);
+        let element: Element = parent
+            .as_ref()
+            .to_owned()
+            .try_into()
+            .expect("element expected to hydrate VComp");
+        (self.hydrator)(element, cursor.clone(), any_props, env.delegation().cloned());
+        let node = self.last_node();
+        if let Some(ref node) = node {
+            *cursor = node.next_sibling();
+        }
+        node
+    }
+}
+
+/// Writes properties with `Properties::fmt_debug`.
+struct PropsDump<'a, T: 'a>(&'a T);
+
+impl<'a, T: Properties> fmt::Display for PropsDump<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt_debug(f)
+    }
+}
+
+impl<COMP: Component> fmt::Debug for VComp<COMP> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        dump::Printer::new(f).comp(self)
     }
 }
 
 impl<COMP: Component> PartialEq for VComp<COMP> {
+    /// Components are equal if they have the same type, key and properties.
+    /// Rendered components are compared by the properties they were rendered with.
     fn eq(&self, other: &VComp<COMP>) -> bool {
-        self.type_id == other.type_id
+        if self.type_id != other.type_id || self.key != other.key {
+            return false;
+        }
+        match (self.props, other.props) {
+            (Some(props_a), Some(props_b)) => (self.comparator)(props_a, props_b),
+            _ => false,
+        }
     }
 }
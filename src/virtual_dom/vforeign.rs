@@ -0,0 +1,219 @@
+//! This module contains the implementation of a virtual foreign node `VForeign`.
+
+use std::cell::RefCell;
+use std::cmp::PartialEq;
+use std::fmt;
+use std::marker::PhantomData;
+use stdweb::web::{INode, Node};
+use html::{Component, Scope};
+use callback::Callback;
+use super::{Reform, VDiff, VNode};
+
+/// A DOM node which is owned by other code, a widget of a JS library for example.
+/// The node is inserted once and kept in place while the next renders
+/// have a `VForeign` with the same node. Its children are never touched.
+/// If a render moves the node to another place, the owner is not notified.
+pub struct VForeign<COMP: Component> {
+    /// A key of the node which used to match it with
+    /// the same node of the previous render.
+    pub key: Option<String>,
+    /// The embedded node.
+    pub node: Node,
+    /// A callback which is called when the node is removed from the tree.
+    pub ondetach: Option<Callback<Node>>,
+    _comp: PhantomData<COMP>,
+}
+
+impl<COMP: Component> VForeign<COMP> {
+    /// Creates a virtual node which embeds the node.
+    pub fn new<T: INode>(node: &T) -> Self {
+        VForeign {
+            key: None,
+            node: node.as_node().to_owned(),
+            ondetach: None,
+            _comp: PhantomData,
+        }
+    }
+
+    /// Sets a callback which gets the node when it is removed from the tree,
+    /// so the owner could destroy the widget or keep it to embed again.
+    pub fn ondetach(mut self, callback: Callback<Node>) -> Self {
+        self.ondetach = Some(callback);
+        self
+    }
+
+    /// Sets a key which used to match the node with the same
+    /// node of the previous render.
+    pub fn set_key<T: ToString>(&mut self, key: &T) {
+        self.key = Some(key.to_string());
+    }
+
+    /// Notifies the owner that the node was removed with a parent element.
+    pub(crate) fn release(&mut self) {
+        if let Some(ref ondetach) = self.ondetach {
+            notify(&self.node, ondetach);
+        }
+    }
+}
+
+/// Foreign nodes which were embedded and detached by the current render.
+#[derive(Default)]
+struct Moves {
+    embedded: Vec<Node>,
+    detached: Vec<(Node, Callback<Node>)>,
+}
+
+thread_local! {
+    static MOVES: RefCell<Option<Moves>> = RefCell::new(None);
+}
+
+/// Tracks foreign nodes while a component renders its tree. The owners of detached
+/// nodes are notified when the tracker is dropped and only if the nodes were not
+/// embedded again by the same render, so moved nodes keep their widgets.
+pub(crate) struct MovesTracker {
+    started: bool,
+}
+
+impl MovesTracker {
+    /// Starts tracking, nested trackers (of nested components) join the outer one.
+    pub(crate) fn start() -> Self {
+        let started = MOVES.with(|moves| {
+            let mut moves = moves.borrow_mut();
+            if moves.is_none() {
+                *moves = Some(Moves::default());
+                true
+            } else {
+                false
+            }
+        });
+        MovesTracker { started }
+    }
+}
+
+impl Drop for MovesTracker {
+    fn drop(&mut self) {
+        if !self.started {
+            return;
+        }
+        if let Some(moves) = MOVES.with(|moves| moves.borrow_mut().take()) {
+            for (node, ondetach) in moves.detached {
+                if !moves.embedded.contains(&node) {
+                    ondetach.emit(node);
+                }
+            }
+        }
+    }
+}
+
+/// Registers a node which is embedded by the current render.
+fn embed(node: &Node) {
+    MOVES.with(|moves| {
+        if let Some(ref mut moves) = *moves.borrow_mut() {
+            moves.embedded.push(node.clone());
+        }
+    });
+}
+
+/// Returns `true` if the node was embedded by the current render.
+fn is_embedded(node: &Node) -> bool {
+    MOVES.with(|moves| {
+        moves
+            .borrow()
+            .as_ref()
+            .map_or(false, |moves| moves.embedded.contains(node))
+    })
+}
+
+/// Notifies the owner of a detached node, at the end of the current render if it's tracked.
+fn notify(node: &Node, ondetach: &Callback<Node>) {
+    let emit_now = MOVES.with(|moves| match *moves.borrow_mut() {
+        Some(ref mut moves) => {
+            moves.detached.push((node.clone(), ondetach.clone()));
+            false
+        }
+        None => true,
+    });
+    if emit_now {
+        ondetach.emit(node.clone());
+    }
+}
+
+impl<COMP: Component> VDiff for VForeign<COMP> {
+    type Component = COMP;
+
+    /// Removes the node from parent and notifies the owner. The node
+    /// is kept if a new node of the same render has embedded it already.
+    fn detach(&mut self, parent: &Node) -> Option<Node> {
+        let sibling = self.node.next_sibling();
+        if !is_embedded(&self.node) && parent.remove_child(&self.node).is_err() {
+            warn!("Node not found to remove VForeign");
+        }
+        self.release();
+        sibling
+    }
+
+    /// Inserts the node if the ancestor doesn't embed the same node.
+    fn apply(
+        &mut self,
+        parent: &Node,
+        precursor: Option<&Node>,
+        ancestor: Option<VNode<Self::Component>>,
+        _: &Scope<Self::Component>,
+    ) -> Option<Node> {
+        let reform = {
+            match ancestor {
+                Some(VNode::VForeign(ref vforeign)) if vforeign.node == self.node => Reform::Keep,
+                Some(mut vnode) => {
+                    let node = vnode.detach(parent);
+                    Reform::Before(node)
+                }
+                None => Reform::Before(None),
+            }
+        };
+        match reform {
+            Reform::Keep => {}
+            Reform::Before(node) => {
+                let next = node.or_else(|| precursor.and_then(INode::next_sibling));
+                if let Some(next) = next {
+                    parent
+                        .insert_before(&self.node, &next)
+                        .expect("can't insert foreign node before sibling");
+                } else {
+                    parent.append_child(&self.node);
+                }
+            }
+        }
+        embed(&self.node);
+        Some(self.node.clone())
+    }
+
+    /// The node can't be rendered on a server, so it's inserted before the cursor.
+    fn hydrate(
+        &mut self,
+        parent: &Node,
+        cursor: &mut Option<Node>,
+        _: &Scope<Self::Component>,
+    ) -> Option<Node> {
+        if let Some(ref next) = *cursor {
+            parent
+                .insert_before(&self.node, next)
+                .expect("can't insert foreign node before cursor");
+        } else {
+            parent.append_child(&self.node);
+        }
+        embed(&self.node);
+        Some(self.node.clone())
+    }
+}
+
+impl<COMP: Component> fmt::Debug for VForeign<COMP> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "VForeign({})", self.node.node_name())
+    }
+}
+
+impl<COMP: Component> PartialEq for VForeign<COMP> {
+    fn eq(&self, other: &VForeign<COMP>) -> bool {
+        self.key == other.key && self.node == other.node
+    }
+}
@@ -1,10 +1,16 @@
 //! This module contains fragments implementation.
-use super::{VDiff, VNode, VText};
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use super::{dump, VDiff, VNode, VText};
 use html::{Component, Scope};
-use stdweb::web::Node;
+use stdweb::web::{document, INode, Node};
 
 /// This struct represents a fragment of the Virtual DOM tree.
 pub struct VList<COMP: Component> {
+    /// A key of the fragment which used to match it with
+    /// the same fragment of the previous render.
+    pub key: Option<String>,
     /// The list of children nodes. Which also could have own children.
     pub childs: Vec<VNode<COMP>>,
 }
@@ -12,13 +18,22 @@ pub struct VList<COMP: Component> {
 impl<COMP: Component> VList<COMP> {
     /// Creates a new `VTag` instance with `tag` name (cannot be changed later in DOM).
     pub fn new() -> Self {
-        VList { childs: Vec::new() }
+        VList {
+            key: None,
+            childs: Vec::new(),
+        }
     }
 
     /// Add `VNode` child.
     pub fn add_child(&mut self, child: VNode<COMP>) {
         self.childs.push(child);
     }
+
+    /// Sets a key which used to match the fragment with the same
+    /// fragment of the previous render.
+    pub fn set_key<T: ToString>(&mut self, key: &T) {
+        self.key = Some(key.to_string());
+    }
 }
 
 impl<COMP: Component> VDiff for VList<COMP> {
@@ -40,57 +55,176 @@ impl<COMP: Component> VDiff for VList<COMP> {
         env: &Scope<Self::Component>,
     ) -> Option<Node> {
         // Reuse precursor, because fragment reuse parent
-        let mut precursor = precursor.map(|node| node.to_owned());
-        let mut rights = {
+        let precursor = precursor.map(|node| node.to_owned());
+        let rights = {
             match ancestor {
                 // If element matched this type
                 Some(VNode::VList(mut vlist)) => {
                     // Previously rendered items
-                    vlist.childs.drain(..).map(Some).collect::<Vec<_>>()
+                    vlist.childs.drain(..).collect::<Vec<_>>()
                 }
-                Some(mut vnode) => {
+                Some(vnode) => {
                     // Use the current node as a single fragment list
                     // and let the `apply` of `VNode` to handle it.
-                    vec![Some(vnode)]
+                    vec![vnode]
                 }
                 None => Vec::new(),
             }
         };
-        // Collect elements of an ancestor if exists or use an empty vec
-        // TODO DRY?!
-        if self.childs.is_empty() {
+        if self.childs.is_empty() && precursor.is_none() {
             // Fixes: https://github.com/DenisKolodin/yew/issues/294
             // Without a placeholder the next element becomes first
             // and corrupts the order of rendering
-            // We use empty text element to stake out a place
+            // We use empty text element to stake out a place.
+            // It's not necessary if there is a precursor, because
+            // nodes rendered later will be placed after it.
+            // The placeholder is not an element and has no text, so CSS
+            // selectors like `:first-child` and `:empty` ignore it, but it's
+            // a `firstChild` of the parent for the DOM API.
             let placeholder = VText::new("".into());
             self.childs.push(placeholder.into());
         }
-        let mut lefts = self.childs.iter_mut().map(Some).collect::<Vec<_>>();
-        // Process children
-        let diff = lefts.len() as i32 - rights.len() as i32;
-        if diff > 0 {
-            for _ in 0..diff {
-                rights.push(None);
-            }
-        } else if diff < 0 {
-            for _ in 0..-diff {
-                lefts.push(None);
-            }
+        apply_children(parent, precursor, &mut self.childs, rights, env)
+    }
+
+    fn hydrate(
+        &mut self,
+        parent: &Node,
+        cursor: &mut Option<Node>,
+        env: &Scope<Self::Component>,
+    ) -> Option<Node> {
+        if self.childs.is_empty() {
+            // The same placeholder as `apply` uses
+            let placeholder = VText::new("".into());
+            self.childs.push(placeholder.into());
         }
-        for pair in lefts.into_iter().zip(rights) {
-            match pair {
-                (Some(left), right) => {
-                    precursor = left.apply(parent, precursor.as_ref(), right, &env);
-                }
-                (None, Some(mut right)) => {
+        let mut last = None;
+        for child in &mut self.childs {
+            last = child.hydrate(parent, cursor, env).or(last);
+        }
+        last
+    }
+}
+
+/// Renders a sequence of children over the children of the previous render.
+///
+/// If none of the nodes has a key, children are paired by their positions.
+/// Otherwise keyed children are paired by keys (and their DOM nodes are moved
+/// to the new positions) and the rest of them are paired by order. Keys of
+/// siblings have to be unique, debug builds panic on duplicates.
+/// Returns the last rendered node which could be used as a precursor.
+pub(crate) fn apply_children<COMP: Component>(
+    parent: &Node,
+    precursor: Option<Node>,
+    lefts: &mut [VNode<COMP>],
+    rights: Vec<VNode<COMP>>,
+    env: &Scope<COMP>,
+) -> Option<Node> {
+    let keyed = lefts.iter().chain(rights.iter()).any(|node| node.key().is_some());
+    if !keyed {
+        let mut precursor = precursor;
+        let mut rights = rights.into_iter();
+        for left in lefts.iter_mut() {
+            precursor = left.apply(parent, precursor.as_ref(), rights.next(), env);
+        }
+        for mut right in rights {
+            right.detach(parent);
+        }
+        return precursor;
+    }
+
+    // A duplicate is paired with nothing in release builds, so it's recreated on every render
+    if cfg!(debug_assertions) {
+        let mut keys = HashSet::new();
+        for key in lefts.iter().filter_map(VNode::key) {
+            assert!(keys.insert(key), "duplicate key of siblings: {}", key);
+        }
+    }
+
+    // Without a precursor we don't know where the children start,
+    // because moved nodes have to be placed before the first
+    // previously rendered node. We stake out that place with
+    // an empty text node.
+    let marker = {
+        if precursor.is_none() {
+            rights.iter().filter_map(VNode::first_node).next().map(|first| {
+                let marker = document().create_text_node("");
+                parent
+                    .insert_before(&marker, &first)
+                    .expect("can't insert marker before the first child");
+                marker.as_node().to_owned()
+            })
+        } else {
+            None
+        }
+    };
+
+    let mut keyed_rights = HashMap::new();
+    let mut unkeyed_rights = VecDeque::new();
+    for mut right in rights {
+        match right.key().map(String::from) {
+            Some(key) => match keyed_rights.entry(key) {
+                Entry::Occupied(entry) => {
+                    warn!("duplicate key in the previous render: {}", entry.key());
                     right.detach(parent);
                 }
-                (None, None) => {
-                    panic!("redundant iterations during diff");
+                Entry::Vacant(entry) => {
+                    entry.insert(right);
                 }
+            },
+            None => unkeyed_rights.push_back(right),
+        }
+    }
+
+    let pairs = lefts
+        .iter()
+        .map(|left| match left.key() {
+            Some(key) => keyed_rights.remove(key),
+            None => unkeyed_rights.pop_front(),
+        })
+        .collect::<Vec<_>>();
+
+    // Remove the nodes which have no pairs, so they don't get in the way of moves.
+    for (_, mut right) in keyed_rights.drain() {
+        right.detach(parent);
+    }
+    for mut right in unkeyed_rights.drain(..) {
+        right.detach(parent);
+    }
+
+    let mut precursor = precursor.or_else(|| marker.clone());
+    for (left, right) in lefts.iter_mut().zip(pairs) {
+        if let Some(ref right) = right {
+            let next = match precursor {
+                Some(ref precursor) => precursor.next_sibling(),
+                None => parent.first_child(),
+            };
+            if right.first_node() != next {
+                right.move_before(parent, next.as_ref());
             }
         }
-        precursor
+        precursor = left.apply(parent, precursor.as_ref(), right, env).or(precursor);
+    }
+
+    if let Some(marker) = marker {
+        parent
+            .remove_child(&marker)
+            .expect("can't remove marker of children");
+        if precursor.as_ref() == Some(&marker) {
+            return None;
+        }
+    }
+    precursor
+}
+
+impl<COMP: Component> fmt::Debug for VList<COMP> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        dump::Printer::new(f).list(self)
+    }
+}
+
+impl<COMP: Component> PartialEq for VList<COMP> {
+    fn eq(&self, other: &VList<COMP>) -> bool {
+        self.key == other.key && self.childs == other.childs
     }
 }
@@ -1,7 +1,7 @@
 //! This module contains the implementation of abstract virtual node.
 
-use super::{VComp, VDiff, VList, VTag, VText};
-use html::{Component, Renderable, Scope};
+use super::{dump, VChild, VComp, VDiff, VForeign, VList, VPortal, VRaw, VTag, VText};
+use html::{Component, NodeBound, NodeBounds, Renderable, Scope};
 use std::cmp::PartialEq;
 use std::fmt;
 use stdweb::web::{INode, Node};
@@ -16,10 +16,159 @@ pub enum VNode<COMP: Component> {
     VComp(VComp<COMP>),
     /// A holder for a list of other nodes.
     VList(VList<COMP>),
+    /// A bind between `VRaw` and nodes parsed from a raw HTML.
+    VRaw(VRaw<COMP>),
+    /// A node owned by other code which is kept in place between renders.
+    VForeign(VForeign<COMP>),
+    /// A holder of a node which is rendered into another element.
+    VPortal(VPortal<COMP>),
+    /// A node of a parent component which is rendered by this component.
+    VChild(VChild),
     /// A holder for any `Node` (necessary for replacing node).
     VRef(Node),
 }
 
+impl<COMP: Component> VNode<COMP> {
+    /// Returns the key of the node if it was set with the `key` attribute.
+    pub fn key(&self) -> Option<&str> {
+        match *self {
+            VNode::VTag(ref vtag) => vtag.key.as_deref(),
+            VNode::VComp(ref vcomp) => vcomp.key.as_deref(),
+            VNode::VList(ref vlist) => vlist.key.as_deref(),
+            VNode::VForeign(ref vforeign) => vforeign.key.as_deref(),
+            VNode::VPortal(ref vportal) => vportal.key.as_deref(),
+            VNode::VChild(ref vchild) => vchild.key(),
+            VNode::VText(_) | VNode::VRaw(_) | VNode::VRef(_) => None,
+        }
+    }
+
+    /// Returns the first DOM node which was rendered for this virtual node.
+    pub(crate) fn first_node(&self) -> Option<Node> {
+        match *self {
+            VNode::VTag(ref vtag) => vtag.reference.as_ref().map(|e| e.as_node().to_owned()),
+            VNode::VText(ref vtext) => vtext.reference.as_ref().map(|t| t.as_node().to_owned()),
+            VNode::VComp(ref vcomp) => vcomp.first_node(),
+            VNode::VList(ref vlist) => vlist.childs.iter().filter_map(VNode::first_node).next(),
+            VNode::VRaw(ref vraw) => vraw.reference.first().cloned(),
+            VNode::VForeign(ref vforeign) => Some(vforeign.node.clone()),
+            VNode::VPortal(ref vportal) => vportal.reference.as_ref().map(|t| t.as_node().to_owned()),
+            VNode::VChild(ref vchild) => vchild.first_node(),
+            VNode::VRef(ref node) => Some(node.to_owned()),
+        }
+    }
+
+    /// Returns the first and the last DOM nodes of this virtual node.
+    /// A component keeps them to be placed and moved by its parent.
+    pub(crate) fn bounds(&self) -> Option<NodeBounds> {
+        match (self.bound(true), self.bound(false)) {
+            (Some(first), Some(last)) => Some(NodeBounds::new(first, last)),
+            _ => None,
+        }
+    }
+
+    /// Returns the first or the last bound of DOM nodes of this virtual node.
+    pub(crate) fn bound(&self, first: bool) -> Option<NodeBound> {
+        match *self {
+            VNode::VTag(ref vtag) => vtag.reference.as_ref().map(|e| NodeBound::Node(e.as_node().to_owned())),
+            VNode::VText(ref vtext) => vtext.reference.as_ref().map(|t| NodeBound::Node(t.as_node().to_owned())),
+            VNode::VComp(ref vcomp) => Some(vcomp.bound()),
+            VNode::VList(ref vlist) => {
+                if first {
+                    vlist.childs.iter().filter_map(|child| child.bound(true)).next()
+                } else {
+                    vlist.childs.iter().rev().filter_map(|child| child.bound(false)).next()
+                }
+            }
+            VNode::VRaw(ref vraw) => {
+                let node = if first { vraw.reference.first() } else { vraw.reference.last() };
+                node.map(|node| NodeBound::Node(node.to_owned()))
+            }
+            VNode::VForeign(ref vforeign) => Some(NodeBound::Node(vforeign.node.clone())),
+            VNode::VPortal(ref vportal) => vportal.reference.as_ref().map(|t| NodeBound::Node(t.as_node().to_owned())),
+            VNode::VChild(ref vchild) => vchild.bound(first),
+            VNode::VRef(ref node) => Some(NodeBound::Node(node.to_owned())),
+        }
+    }
+
+    /// Releases listeners and destroys components of the node which DOM
+    /// was removed with a parent element, so it's not necessary to remove
+    /// every node from the DOM separately.
+    pub(crate) fn release(&mut self) {
+        match *self {
+            VNode::VTag(ref mut vtag) => vtag.release(),
+            VNode::VComp(ref mut vcomp) => vcomp.release(),
+            VNode::VForeign(ref mut vforeign) => vforeign.release(),
+            VNode::VPortal(ref mut vportal) => vportal.release(),
+            VNode::VChild(ref mut vchild) => vchild.release(),
+            VNode::VList(ref mut vlist) => {
+                for child in &mut vlist.childs {
+                    child.release();
+                }
+            }
+            VNode::VText(_) | VNode::VRaw(_) | VNode::VRef(_) => {}
+        }
+    }
+
+    /// Returns `true` if the node or its children have listeners.
+    pub(crate) fn has_listeners(&self) -> bool {
+        match *self {
+            VNode::VTag(ref vtag) => !vtag.listeners.is_empty() || vtag.childs.iter().any(VNode::has_listeners),
+            VNode::VList(ref vlist) => vlist.childs.iter().any(VNode::has_listeners),
+            VNode::VPortal(ref vportal) => vportal.node.has_listeners(),
+            VNode::VChild(ref vchild) => vchild.has_listeners(),
+            VNode::VText(_) | VNode::VComp(_) | VNode::VRaw(_) | VNode::VForeign(_) | VNode::VRef(_) => false,
+        }
+    }
+
+    /// Moves all DOM nodes of this virtual node before the `next` sibling
+    /// or to the end of the `parent` if there is no sibling.
+    pub(crate) fn move_before(&self, parent: &Node, next: Option<&Node>) {
+        let insert = |node: &Node| {
+            if let Some(next) = next {
+                parent
+                    .insert_before(node, next)
+                    .expect("can't move node before sibling");
+            } else {
+                parent.append_child(node);
+            }
+        };
+        match *self {
+            VNode::VList(ref vlist) => {
+                for child in &vlist.childs {
+                    child.move_before(parent, next);
+                }
+            }
+            VNode::VRaw(ref vraw) => {
+                for node in &vraw.reference {
+                    insert(node);
+                }
+            }
+            VNode::VChild(ref vchild) => vchild.move_before(parent, next),
+            VNode::VComp(ref vcomp) => {
+                // A component could render a fragment, its nodes
+                // are the siblings from the first node to the last one
+                if let (Some(first), Some(last)) = (vcomp.first_node(), vcomp.last_node()) {
+                    let mut nodes = vec![first];
+                    while nodes.last() != Some(&last) {
+                        match nodes.last().and_then(INode::next_sibling) {
+                            Some(node) => nodes.push(node),
+                            None => break,
+                        }
+                    }
+                    for node in &nodes {
+                        insert(node);
+                    }
+                }
+            }
+            _ => {
+                if let Some(node) = self.first_node() {
+                    insert(&node);
+                }
+            }
+        }
+    }
+}
+
 impl<COMP: Component> VDiff for VNode<COMP> {
     type Component = COMP;
 
@@ -30,6 +179,10 @@ impl<COMP: Component> VDiff for VNode<COMP> {
             VNode::VText(ref mut vtext) => vtext.detach(parent),
             VNode::VComp(ref mut vcomp) => vcomp.detach(parent),
             VNode::VList(ref mut vlist) => vlist.detach(parent),
+            VNode::VRaw(ref mut vraw) => vraw.detach(parent),
+            VNode::VForeign(ref mut vforeign) => vforeign.detach(parent),
+            VNode::VPortal(ref mut vportal) => vportal.detach(parent),
+            VNode::VChild(ref mut vchild) => vchild.detach(parent),
             VNode::VRef(ref node) => {
                 let sibling = node.next_sibling();
                 parent
@@ -52,6 +205,10 @@ impl<COMP: Component> VDiff for VNode<COMP> {
             VNode::VText(ref mut vtext) => vtext.apply(parent, precursor, ancestor, env),
             VNode::VComp(ref mut vcomp) => vcomp.apply(parent, precursor, ancestor, env),
             VNode::VList(ref mut vlist) => vlist.apply(parent, precursor, ancestor, env),
+            VNode::VRaw(ref mut vraw) => vraw.apply(parent, precursor, ancestor, env),
+            VNode::VForeign(ref mut vforeign) => vforeign.apply(parent, precursor, ancestor, env),
+            VNode::VPortal(ref mut vportal) => vportal.apply(parent, precursor, ancestor, env),
+            VNode::VChild(ref mut vchild) => vchild.apply(parent, precursor, ancestor),
             VNode::VRef(ref mut node) => {
                 let sibling = match ancestor {
                     Some(mut n) => n.detach(parent),
@@ -69,6 +226,33 @@ impl<COMP: Component> VDiff for VNode<COMP> {
             }
         }
     }
+
+    fn hydrate(
+        &mut self,
+        parent: &Node,
+        cursor: &mut Option<Node>,
+        env: &Scope<Self::Component>,
+    ) -> Option<Node> {
+        match *self {
+            VNode::VTag(ref mut vtag) => vtag.hydrate(parent, cursor, env),
+            VNode::VText(ref mut vtext) => vtext.hydrate(parent, cursor, env),
+            VNode::VComp(ref mut vcomp) => vcomp.hydrate(parent, cursor, env),
+            VNode::VList(ref mut vlist) => vlist.hydrate(parent, cursor, env),
+            VNode::VRaw(ref mut vraw) => vraw.hydrate(parent, cursor, env),
+            VNode::VForeign(ref mut vforeign) => vforeign.hydrate(parent, cursor, env),
+            VNode::VPortal(ref mut vportal) => vportal.hydrate(parent, cursor, env),
+            VNode::VChild(ref mut vchild) => vchild.hydrate(parent, cursor),
+            VNode::VRef(_) => {
+                // The node is owned by the tree, so it's not possible to bind
+                // it to an existing node. Put it in place of the current one.
+                let ancestor = cursor.take().map(|current| {
+                    *cursor = current.next_sibling();
+                    VNode::VRef(current)
+                });
+                self.apply(parent, None, ancestor, env)
+            }
+        }
+    }
 }
 
 impl<COMP: Component> From<VText<COMP>> for VNode<COMP> {
@@ -89,6 +273,30 @@ impl<COMP: Component> From<VTag<COMP>> for VNode<COMP> {
     }
 }
 
+impl<COMP: Component> From<VRaw<COMP>> for VNode<COMP> {
+    fn from(vraw: VRaw<COMP>) -> Self {
+        VNode::VRaw(vraw)
+    }
+}
+
+impl<COMP: Component> From<VForeign<COMP>> for VNode<COMP> {
+    fn from(vforeign: VForeign<COMP>) -> Self {
+        VNode::VForeign(vforeign)
+    }
+}
+
+impl<COMP: Component> From<VPortal<COMP>> for VNode<COMP> {
+    fn from(vportal: VPortal<COMP>) -> Self {
+        VNode::VPortal(vportal)
+    }
+}
+
+impl<COMP: Component> From<VChild> for VNode<COMP> {
+    fn from(vchild: VChild) -> Self {
+        VNode::VChild(vchild)
+    }
+}
+
 impl<COMP: Component> From<VComp<COMP>> for VNode<COMP> {
     fn from(vcomp: VComp<COMP>) -> Self {
         VNode::VComp(vcomp)
@@ -109,13 +317,7 @@ impl<'a, COMP: Component> From<&'a dyn Renderable<COMP>> for VNode<COMP> {
 
 impl<COMP: Component> fmt::Debug for VNode<COMP> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match *self {
-            VNode::VTag(ref vtag) => vtag.fmt(f),
-            VNode::VText(ref vtext) => vtext.fmt(f),
-            VNode::VComp(_) => "Component<>".fmt(f),
-            VNode::VList(_) => "List<>".fmt(f),
-            VNode::VRef(_) => "NodeReference<>".fmt(f),
-        }
+        dump::Printer::new(f).node(self)
     }
 }
 
@@ -130,10 +332,34 @@ impl<COMP: Component> PartialEq for VNode<COMP> {
                 VNode::VText(ref vtext_b) => vtext_a == vtext_b,
                 _ => false,
             },
-            _ => {
-                // TODO Implement it
-                false
-            }
+            VNode::VRaw(ref vraw_a) => match *other {
+                VNode::VRaw(ref vraw_b) => vraw_a == vraw_b,
+                _ => false,
+            },
+            VNode::VForeign(ref vforeign_a) => match *other {
+                VNode::VForeign(ref vforeign_b) => vforeign_a == vforeign_b,
+                _ => false,
+            },
+            VNode::VPortal(ref vportal_a) => match *other {
+                VNode::VPortal(ref vportal_b) => vportal_a == vportal_b,
+                _ => false,
+            },
+            VNode::VComp(ref vcomp_a) => match *other {
+                VNode::VComp(ref vcomp_b) => vcomp_a == vcomp_b,
+                _ => false,
+            },
+            VNode::VList(ref vlist_a) => match *other {
+                VNode::VList(ref vlist_b) => vlist_a == vlist_b,
+                _ => false,
+            },
+            VNode::VChild(ref vchild_a) => match *other {
+                VNode::VChild(ref vchild_b) => vchild_a == vchild_b,
+                _ => false,
+            },
+            VNode::VRef(ref node_a) => match *other {
+                VNode::VRef(ref node_b) => node_a == node_b,
+                _ => false,
+            },
         }
     }
 }
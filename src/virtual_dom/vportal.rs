@@ -0,0 +1,162 @@
+//! This module contains the implementation of a portal `VPortal`.
+
+use std::cmp::PartialEq;
+use std::fmt;
+use stdweb::web::{document, Element, INode, Node, TextNode};
+use html::{Component, Scope};
+use super::{dump, Reform, VDiff, VNode};
+use super::Delegation;
+
+/// A portal renders its node into another element (a host), modals and tooltips
+/// for example, to get out of containers with `overflow: hidden`. The node is still
+/// diffed and removed with the tree which contains the portal and listeners send
+/// messages to the component which rendered it.
+///
+/// The portal keeps an empty text node in its place of the tree. If the app
+/// delegates listeners, the portal delegates listeners of its node to the host,
+/// because events of the host don't bubble to the mount element of the app.
+pub struct VPortal<COMP: Component> {
+    /// A key of the portal which used to match it with
+    /// the same portal of the previous render.
+    pub key: Option<String>,
+    /// The element which the node is rendered into.
+    pub host: Element,
+    /// The node which is rendered into the host.
+    pub node: Box<VNode<COMP>>,
+    /// A reference to the placeholder of the portal.
+    pub reference: Option<TextNode>,
+    /// A registry of delegated listeners of the node rooted at the host.
+    delegation: Option<Delegation>,
+}
+
+impl<COMP: Component> VPortal<COMP> {
+    /// Creates a portal which renders the node into the host element.
+    pub fn new(host: Element, node: VNode<COMP>) -> Self {
+        VPortal {
+            key: None,
+            host,
+            node: Box::new(node),
+            reference: None,
+            delegation: None,
+        }
+    }
+
+    /// Sets a key which used to match the portal with the same
+    /// portal of the previous render.
+    pub fn set_key<T: ToString>(&mut self, key: &T) {
+        self.key = Some(key.to_string());
+    }
+
+    /// Removes the node from the host when the placeholder was removed with
+    /// a parent element, because the host is not a part of the parent.
+    pub(crate) fn release(&mut self) {
+        self.node.detach(self.host.as_node());
+    }
+
+    /// Renders the node into the host with the registry of the portal
+    /// if the app delegates listeners.
+    fn apply_node(&mut self, ancestor: Option<VNode<COMP>>, env: &Scope<COMP>) {
+        if env.delegation().is_some() && self.delegation.is_none() {
+            self.delegation = Some(Delegation::new(self.host.clone()));
+        }
+        let env = env.clone().with_delegation(self.delegation.clone());
+        self.node.apply(self.host.as_node(), None, ancestor, &env);
+    }
+}
+
+impl<COMP: Component> VDiff for VPortal<COMP> {
+    type Component = COMP;
+
+    /// Removes the node from the host and the placeholder from parent.
+    fn detach(&mut self, parent: &Node) -> Option<Node> {
+        self.node.detach(self.host.as_node());
+        let node = self.reference.take()
+            .expect("tried to remove not rendered VPortal from DOM");
+        let sibling = node.next_sibling();
+        if parent.remove_child(&node).is_err() {
+            warn!("Node not found to remove VPortal");
+        }
+        sibling
+    }
+
+    /// Renders the node over the node of the ancestor portal if it has the same host.
+    fn apply(
+        &mut self,
+        parent: &Node,
+        precursor: Option<&Node>,
+        ancestor: Option<VNode<Self::Component>>,
+        env: &Scope<Self::Component>,
+    ) -> Option<Node> {
+        assert!(self.reference.is_none(), "reference is ignored so must not be set");
+        let (reform, ancestor_node) = {
+            match ancestor {
+                Some(VNode::VPortal(mut vportal)) => {
+                    if self.host == vportal.host {
+                        self.reference = vportal.reference.take();
+                        self.delegation = vportal.delegation.take();
+                        (Reform::Keep, Some(*vportal.node))
+                    } else {
+                        let node = vportal.detach(parent);
+                        (Reform::Before(node), None)
+                    }
+                }
+                Some(mut vnode) => {
+                    let node = vnode.detach(parent);
+                    (Reform::Before(node), None)
+                }
+                None => (Reform::Before(None), None),
+            }
+        };
+        match reform {
+            Reform::Keep => {}
+            Reform::Before(node) => {
+                let element = document().create_text_node("");
+                let next = node.or_else(|| precursor.and_then(INode::next_sibling));
+                if let Some(next) = next {
+                    parent
+                        .insert_before(&element, &next)
+                        .expect("can't insert portal before sibling");
+                } else {
+                    parent.append_child(&element);
+                }
+                self.reference = Some(element);
+            }
+        }
+        self.apply_node(ancestor_node, env);
+        self.reference.as_ref().map(|t| t.as_node().to_owned())
+    }
+
+    /// The node of the portal is not rendered on a server, so the placeholder
+    /// is inserted before the cursor and the node is rendered into the host.
+    fn hydrate(
+        &mut self,
+        parent: &Node,
+        cursor: &mut Option<Node>,
+        env: &Scope<Self::Component>,
+    ) -> Option<Node> {
+        assert!(self.reference.is_none(), "reference is ignored so must not be set");
+        let element = document().create_text_node("");
+        if let Some(ref next) = *cursor {
+            parent
+                .insert_before(&element, next)
+                .expect("can't insert portal before cursor");
+        } else {
+            parent.append_child(&element);
+        }
+        self.reference = Some(element);
+        self.apply_node(None, env);
+        self.reference.as_ref().map(|t| t.as_node().to_owned())
+    }
+}
+
+impl<COMP: Component> fmt::Debug for VPortal<COMP> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        dump::Printer::new(f).portal(self)
+    }
+}
+
+impl<COMP: Component> PartialEq for VPortal<COMP> {
+    fn eq(&self, other: &VPortal<COMP>) -> bool {
+        self.key == other.key && self.host == other.host && self.node == other.node
+    }
+}
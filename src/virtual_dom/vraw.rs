@@ -0,0 +1,190 @@
+//! This module contains the implementation of a virtual raw HTML node `VRaw`.
+
+use std::cmp::PartialEq;
+use std::fmt;
+use std::marker::PhantomData;
+use stdweb::unstable::TryInto;
+use stdweb::web::{document, INode, Node};
+use html::{Component, Scope};
+use super::{hydration_mismatch, Reform, VDiff, VNode};
+
+const XHTML_NAMESPACE: &str = "http://www.w3.org/1999/xhtml";
+const SVG_NAMESPACE: &str = "http://www.w3.org/2000/svg";
+const MATHML_NAMESPACE: &str = "http://www.w3.org/1998/Math/MathML";
+
+/// A fragment of a raw HTML. It's parsed to DOM nodes with `innerHTML` of
+/// an inert `template` element and it's parsed again only if the HTML had changed.
+pub struct VRaw<COMP: Component> {
+    /// Contains a raw HTML of the node, it's cleaned already if the node has a sanitizer.
+    pub html: String,
+    /// References to the nodes parsed from the HTML.
+    pub reference: Vec<Node>,
+    _comp: PhantomData<COMP>,
+}
+
+impl<COMP: Component> VRaw<COMP> {
+    /// Creates new virtual raw node with a HTML.
+    pub fn new<T: Into<String>>(html: T) -> Self {
+        VRaw {
+            html: html.into(),
+            reference: Vec::new(),
+            _comp: PhantomData,
+        }
+    }
+
+    /// Cleans the HTML with a sanitizer, use it for content provided by users.
+    /// The sanitizer is called once, the node keeps the cleaned HTML to be
+    /// compared, parsed and rendered to a string.
+    pub fn sanitize<F>(mut self, sanitizer: F) -> Self
+    where
+        F: FnOnce(&str) -> String,
+    {
+        self.html = sanitizer(&self.html);
+        self
+    }
+
+    /// Parses the HTML to detached nodes. Scripts of the HTML are not executed.
+    /// Children of SVG and MathML elements are parsed inside an element
+    /// of that namespace, other namespaces are not supported.
+    fn parse(&self, parent: &Node) -> Vec<Node> {
+        let namespace: Option<String> = js!(return @{parent}.namespaceURI;)
+            .try_into()
+            .expect("can't get namespace of a parent of raw HTML");
+        let wrapper = match namespace.as_deref() {
+            None | Some(XHTML_NAMESPACE) => "",
+            Some(SVG_NAMESPACE) => "svg",
+            Some(MATHML_NAMESPACE) => "math",
+            Some(namespace) => panic!("raw HTML can't be parsed in {} namespace", namespace),
+        };
+        let nodes = js! {
+            var wrapper = @{wrapper};
+            var template = document.createElement("template");
+            var content = template.content;
+            if (wrapper) {
+                template.innerHTML = "<" + wrapper + ">" + @{&self.html} + "</" + wrapper + ">";
+                content = template.content.firstChild;
+            } else {
+                template.innerHTML = @{&self.html};
+            }
+            return Array.prototype.slice.call(content.childNodes);
+        };
+        nodes.try_into().expect("can't parse raw HTML")
+    }
+
+    /// Inserts the parsed nodes before the `next` node or appends them.
+    /// An empty text node stakes out a place of the empty HTML.
+    fn insert(&mut self, parent: &Node, next: Option<&Node>) {
+        let mut nodes = self.parse(parent);
+        if nodes.is_empty() {
+            nodes.push(document().create_text_node("").as_node().to_owned());
+        }
+        for node in &nodes {
+            if let Some(next) = next {
+                parent
+                    .insert_before(node, next)
+                    .expect("can't insert raw node before sibling");
+            } else {
+                parent.append_child(node);
+            }
+        }
+        self.reference = nodes;
+    }
+}
+
+impl<COMP: Component> VDiff for VRaw<COMP> {
+    type Component = COMP;
+
+    /// Removes all nodes of the HTML from parent.
+    fn detach(&mut self, parent: &Node) -> Option<Node> {
+        let sibling = self.reference.last().and_then(INode::next_sibling);
+        for node in self.reference.drain(..) {
+            if parent.remove_child(&node).is_err() {
+                warn!("Node not found to remove VRaw");
+            }
+        }
+        sibling
+    }
+
+    /// Renders virtual node over nodes of the ancestor, but
+    /// only if the sanitized HTML had changed.
+    fn apply(
+        &mut self,
+        parent: &Node,
+        precursor: Option<&Node>,
+        ancestor: Option<VNode<Self::Component>>,
+        _: &Scope<Self::Component>,
+    ) -> Option<Node> {
+        assert!(self.reference.is_empty(), "reference is ignored so must not be set");
+        let reform = {
+            match ancestor {
+                Some(VNode::VRaw(mut vraw)) if vraw.html == self.html => {
+                    self.reference = vraw.reference.drain(..).collect();
+                    Reform::Keep
+                }
+                Some(mut vnode) => {
+                    let node = vnode.detach(parent);
+                    Reform::Before(node)
+                }
+                None => Reform::Before(None),
+            }
+        };
+        match reform {
+            Reform::Keep => {}
+            Reform::Before(node) => {
+                let next = node.or_else(|| precursor.and_then(INode::next_sibling));
+                self.insert(parent, next.as_ref());
+            }
+        }
+        self.reference.last().cloned()
+    }
+
+    /// Binds the virtual node to as many existing nodes as the HTML contains.
+    fn hydrate(
+        &mut self,
+        parent: &Node,
+        cursor: &mut Option<Node>,
+        _: &Scope<Self::Component>,
+    ) -> Option<Node> {
+        assert!(self.reference.is_empty(), "reference is ignored so must not be set");
+        let parsed = self.parse(parent);
+        if parsed.is_empty() {
+            // An empty HTML is not rendered, the placeholder has to be created
+            self.insert(parent, cursor.as_ref());
+            return self.reference.last().cloned();
+        }
+        for node in parsed {
+            let existing = cursor.clone().filter(|current| current.node_name() == node.node_name());
+            match existing {
+                Some(current) => {
+                    *cursor = current.next_sibling();
+                    self.reference.push(current);
+                }
+                None => {
+                    hydration_mismatch(format_args!("expected {} of raw HTML, found {:?}", node.node_name(), cursor));
+                    if let Some(ref sibling) = *cursor {
+                        parent
+                            .insert_before(&node, sibling)
+                            .expect("can't insert raw node before sibling");
+                    } else {
+                        parent.append_child(&node);
+                    }
+                    self.reference.push(node);
+                }
+            }
+        }
+        self.reference.last().cloned()
+    }
+}
+
+impl<COMP: Component> fmt::Debug for VRaw<COMP> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "VRaw({:?})", self.html)
+    }
+}
+
+/// Nodes are equal if they render the same HTML after sanitizing.
+impl<COMP: Component> PartialEq for VRaw<COMP> {
+    fn eq(&self, other: &VRaw<COMP>) -> bool {
+        self.html == other.html
+    }
+}
@@ -7,9 +7,10 @@ use std::fmt;
 use stdweb::web::html_element::TextAreaElement;
 use stdweb::unstable::TryFrom;
 use stdweb::web::html_element::InputElement;
-use stdweb::web::{document, Element, EventListenerHandle, IElement, INode, Node};
-use html::{Component, Scope};
-use super::{Attributes, Classes, Listener, Listeners, Patch, Reform, VDiff, VNode};
+use stdweb::web::{document, Element, IElement, INode, Node};
+use html::{self, Component, NodeRef, Scope};
+use super::{dump, hydration_mismatch, AttachedListener, Attributes, Classes, Listener, Listeners, Patch, PropValue, Props, Reform, Styles, VDiff, VNode};
+use super::vlist::apply_children;
 
 
 /// A type for a virtual
@@ -18,10 +19,15 @@ use super::{Attributes, Classes, Listener, Listeners, Patch, Reform, VDiff, VNod
 pub struct VTag<COMP: Component> {
     /// A tag of the element.
     tag: Cow<'static, str>,
+    /// A key of the element which used to match it with
+    /// the same element of the previous render.
+    pub key: Option<String>,
     /// The namespace the tag belongs to where None is the default namespace.
     pub ns: Option<Cow<'static, str>>,
     /// A reference to the `Element`.
     pub reference: Option<Element>,
+    /// A reference which is filled with the `Element` for components.
+    pub node_ref: NodeRef,
     /// List of attached listeners.
     pub listeners: Listeners<COMP>,
     /// List of attributes.
@@ -30,6 +36,12 @@ pub struct VTag<COMP: Component> {
     pub childs: Vec<VNode<COMP>>,
     /// List of attached classes.
     pub classes: Classes,
+    /// Properties of the inline style. They are set separately from
+    /// `style` attribute, so unchanged properties are not touched.
+    pub styles: Styles,
+    /// DOM properties of the element. They are assigned to the element
+    /// after its children are rendered.
+    pub props: Props,
     /// Contains a value of an
     /// [InputElement](https://developer.mozilla.org/en-US/docs/Web/HTML/Element/input).
     pub value: Option<String>,
@@ -45,7 +57,7 @@ pub struct VTag<COMP: Component> {
     pub checked: bool,
     /// _Service field_. Keeps handler for attached listeners
     /// to have an opportunity to drop them later.
-    captured: Vec<EventListenerHandle>,
+    captured: Vec<AttachedListener>,
 }
 
 impl<COMP: Component> VTag<COMP> {
@@ -57,9 +69,13 @@ impl<COMP: Component> VTag<COMP> {
     {
         VTag {
             tag: tag.into(),
+            key: None,
             ns: ns.into(),
             reference: None,
+            node_ref: NodeRef::default(),
             classes: Classes::new(),
+            styles: Styles::new(),
+            props: Props::new(),
             attributes: Attributes::new(),
             listeners: Vec::new(),
             captured: Vec::new(),
@@ -99,6 +115,33 @@ impl<COMP: Component> VTag<COMP> {
         self.classes = classes.split_whitespace().map(String::from).collect();
     }
 
+    /// Adds classes of a `Classes` list or of a value which could be converted to it.
+    pub fn add_classes<T: Into<html::Classes>>(&mut self, classes: T) {
+        for class in classes.into().iter() {
+            self.classes.insert(class.to_owned());
+        }
+    }
+
+    /// Sets a property of the inline style. Actually it will set by
+    /// [CSSStyleDeclaration.setProperty](https://developer.mozilla.org/en-US/docs/Web/API/CSSStyleDeclaration/setProperty)
+    /// call later.
+    pub fn add_style<T: ToString>(&mut self, name: &str, value: &T) {
+        self.styles.insert(name.to_owned(), value.to_string());
+    }
+
+    /// Adds properties of a `Style` or of a value which could be converted to it.
+    pub fn add_styles<T: Into<html::Style>>(&mut self, style: T) {
+        for (name, value) in style.into().iter() {
+            self.styles.insert(name.to_owned(), value.to_owned());
+        }
+    }
+
+    /// Sets a DOM property of the element. Unlike attributes, properties
+    /// are assigned as JS values: `element[name] = value`.
+    pub fn add_prop<T: Into<PropValue>>(&mut self, name: &str, value: T) {
+        self.props.insert(name.to_owned(), value.into());
+    }
+
     /// Sets `value` for an
     /// [InputElement](https://developer.mozilla.org/en-US/docs/Web/HTML/Element/input).
     pub fn set_value<T: ToString>(&mut self, value: &T) {
@@ -119,6 +162,19 @@ impl<COMP: Component> VTag<COMP> {
         self.checked = value;
     }
 
+    /// Sets a key which used to match the element with the same
+    /// element of the previous render. Keyed elements are moved
+    /// instead of being patched in place.
+    pub fn set_key<T: ToString>(&mut self, key: &T) {
+        self.key = Some(key.to_string());
+    }
+
+    /// Sets a reference which will be filled with the `Element`
+    /// when the tag is rendered.
+    pub fn set_node_ref(&mut self, node_ref: NodeRef) {
+        self.node_ref = node_ref;
+    }
+
     /// Sets `namespace` property of a tag
     pub fn set_namespace<NS>(&mut self, value: NS)
     where
@@ -140,6 +196,71 @@ impl<COMP: Component> VTag<COMP> {
         self.listeners.push(listener);
     }
 
+    /// Removes attached listeners and releases children. The element itself
+    /// stays in the DOM.
+    pub(crate) fn release(&mut self) {
+        for attached in self.captured.drain(..) {
+            attached.remove();
+        }
+        for child in &mut self.childs {
+            child.release();
+        }
+    }
+
+    /// Reads classes and attributes of an element rendered on a server, so hydration
+    /// diffs the tag against them. Attributes which the tag controls with other fields
+    /// (`type`, `value`, `checked`, `xmlns` and `style` merged with the inline style)
+    /// are taken from the tag.
+    fn adopted(&self, element: &Element) -> Self {
+        let mut adopted = VTag::new(self.tag.clone(), self.ns.clone());
+        for (name, value) in get_attributes(element) {
+            match name.as_str() {
+                "class" => adopted.set_classes(&value),
+                "type" if self.kind.is_some() => {}
+                "value" if self.value.is_some() => {}
+                "checked" if self.checked => {}
+                "xmlns" if self.ns.is_some() => {}
+                "style" if !self.styles.is_empty() => {}
+                _ => {
+                    adopted.attributes.insert(name, value);
+                }
+            }
+        }
+        if !self.styles.is_empty() {
+            if let Some(style) = self.attributes.get("style") {
+                adopted.attributes.insert("style".into(), style.to_owned());
+            }
+        }
+        adopted
+    }
+
+    /// Reports classes and attributes which differ from the adopted element.
+    fn report_mismatches(&mut self, adopted: &mut Option<Self>) {
+        for change in self.diff_classes(adopted) {
+            match change {
+                Patch::Add(class, _) | Patch::Replace(class, _) => {
+                    hydration_mismatch(format_args!("missing class {:?} of <{}>", class, self.tag));
+                }
+                Patch::Remove(class) => {
+                    hydration_mismatch(format_args!("redundant class {:?} of <{}>", class, self.tag));
+                }
+            }
+        }
+        for change in self.diff_attributes(adopted) {
+            match change {
+                Patch::Add(name, _) => {
+                    hydration_mismatch(format_args!("missing attribute {:?} of <{}>", name, self.tag));
+                }
+                Patch::Replace(name, value) => {
+                    hydration_mismatch(format_args!("attribute {:?} of <{}> expected to be {:?}", name, self.tag, value));
+                }
+                Patch::Remove(name) => {
+                    hydration_mismatch(format_args!("redundant attribute {:?} of <{}>", name, self.tag));
+                }
+            }
+        }
+    }
+
     /// Compute differences between the ancestor and determine patch changes.
     ///
     /// If there is an ancestor:
@@ -213,6 +334,58 @@ impl<COMP: Component> VTag<COMP> {
         changes
     }
 
+    /// Similar to `diff_attributes` except for properties of the inline style.
+    fn diff_styles(&mut self, ancestor: &mut Option<Self>) -> Vec<Patch<String, String>> {
+        let mut changes = Vec::new();
+        if let &mut Some(ref ancestor) = ancestor {
+            for (name, value) in &self.styles {
+                match ancestor.styles.get(name) {
+                    Some(ancestor_value) if ancestor_value == value => {}
+                    Some(_) => changes.push(Patch::Replace(name.to_owned(), value.to_owned())),
+                    None => changes.push(Patch::Add(name.to_owned(), value.to_owned())),
+                }
+            }
+            let to_remove = ancestor
+                .styles
+                .keys()
+                .filter(|name| !self.styles.contains_key(*name))
+                .map(|name| Patch::Remove(name.to_owned()));
+            changes.extend(to_remove);
+        } else {
+            // Add everything
+            for (name, value) in &self.styles {
+                changes.push(Patch::Add(name.to_owned(), value.to_owned()));
+            }
+        }
+        changes
+    }
+
+    /// Similar to `diff_styles` except for DOM properties.
+    fn diff_props(&mut self, ancestor: &mut Option<Self>) -> Vec<Patch<String, PropValue>> {
+        let mut changes = Vec::new();
+        if let &mut Some(ref ancestor) = ancestor {
+            for (name, value) in &self.props {
+                match ancestor.props.get(name) {
+                    Some(ancestor_value) if ancestor_value == value => {}
+                    Some(_) => changes.push(Patch::Replace(name.to_owned(), value.clone())),
+                    None => changes.push(Patch::Add(name.to_owned(), value.clone())),
+                }
+            }
+            let to_remove = ancestor
+                .props
+                .keys()
+                .filter(|name| !self.props.contains_key(*name))
+                .map(|name| Patch::Remove(name.to_owned()));
+            changes.extend(to_remove);
+        } else {
+            // Add everything
+            for (name, value) in &self.props {
+                changes.push(Patch::Add(name.to_owned(), value.clone()));
+            }
+        }
+        changes
+    }
+
     /// Similar to `diff_attributers` except there is only a single `kind`.
     fn diff_kind(&mut self, ancestor: &mut Option<Self>) -> Option<Patch<String, ()>> {
         match (
@@ -251,6 +424,51 @@ impl<COMP: Component> VTag<COMP> {
         }
     }
 
+    /// Attaches listeners to the element. Listeners attached by the ancestor
+    /// are reused for the listeners of the same kind: JS listeners are kept
+    /// and only handlers are replaced. The rest of them are removed.
+    fn apply_listeners(
+        &mut self,
+        element: &Element,
+        mut captured: Vec<AttachedListener>,
+        env: &Scope<COMP>,
+    ) {
+        for mut listener in self.listeners.drain(..) {
+            let kind = listener.kind();
+            let position = captured.iter().position(|attached| attached.kind() == kind);
+            let reused = position.and_then(|position| {
+                let attached = captured.remove(position);
+                if listener.reattach(&attached) {
+                    Some(attached)
+                } else {
+                    attached.remove();
+                    None
+                }
+            });
+            let attached = reused.unwrap_or_else(|| listener.attach(element, env.clone()));
+            self.captured.push(attached);
+        }
+        for attached in captured {
+            attached.remove();
+        }
+    }
+
+    /// Assigns DOM properties. It's called after children are rendered, because
+    /// properties like `selectedIndex` or `scrollTop` depend on them.
+    fn apply_props(&mut self, element: &Element, ancestor: &mut Option<Self>) {
+        let changes = self.diff_props(ancestor);
+        for change in changes {
+            match change {
+                Patch::Add(name, value) | Patch::Replace(name, value) => {
+                    set_property(element, &name, &value);
+                }
+                Patch::Remove(name) => {
+                    remove_property(element, &name);
+                }
+            }
+        }
+    }
+
     fn apply_diffs(
         &mut self,
         element: &Element,
@@ -271,17 +489,38 @@ impl<COMP: Component> VTag<COMP> {
         }
 
         let changes = self.diff_attributes(ancestor);
+        let mut style_reset = false;
         for change in changes {
             match change {
                 Patch::Add(key, value) | Patch::Replace(key, value) => {
+                    style_reset |= key == "style";
                     set_attribute(element, &key, &value);
                 }
                 Patch::Remove(key) => {
+                    style_reset |= key == "style";
                     remove_attribute(element, &key);
                 }
             }
         }
 
+        // Styles are applied after attributes, because `style` attribute
+        // replaces all properties of the inline style
+        let changes = if style_reset {
+            self.diff_styles(&mut None)
+        } else {
+            self.diff_styles(ancestor)
+        };
+        for change in changes {
+            match change {
+                Patch::Add(name, value) | Patch::Replace(name, value) => {
+                    set_style(element, &name, &value);
+                }
+                Patch::Remove(name) => {
+                    remove_style(element, &name);
+                }
+            }
+        }
+
         // `input` element has extra parameters to control
         // I override behavior of attributes to make it more clear
         // and useful in templates. For example I interpret `checked`
@@ -343,6 +582,11 @@ impl<COMP: Component> VDiff for VTag<COMP> {
     fn detach(&mut self, parent: &Node) -> Option<Node> {
         let node = self.reference.take()
             .expect("tried to remove not rendered VTag from DOM");
+        // The reference could be taken by a new element already
+        if self.node_ref.get().as_ref() == Some(node.as_node()) {
+            self.node_ref.set(None);
+        }
+        self.release();
         let sibling = node.next_sibling();
         if parent.remove_child(&node).is_err() {
             warn!("Node not found to remove VTag");
@@ -417,11 +661,12 @@ impl<COMP: Component> VDiff for VTag<COMP> {
         }
 
         let element = self.reference.clone().expect("element expected");
+        self.node_ref.set(Some(element.as_node().to_owned()));
 
         {
-            let mut ancestor_childs = {
+            let ancestor_childs = {
                 if let Some(ref mut a) = ancestor {
-                    a.childs.drain(..).map(Some).collect::<Vec<_>>()
+                    a.childs.drain(..).collect::<Vec<_>>()
                 } else {
                     Vec::new()
                 }
@@ -429,55 +674,72 @@ impl<COMP: Component> VDiff for VTag<COMP> {
 
             self.apply_diffs(&element, &mut ancestor);
 
-            // Every render it removes all listeners and attach it back later
-            // TODO Compare references of handler to do listeners update better
-            if let Some(mut ancestor) = ancestor {
-                for handle in ancestor.captured.drain(..) {
-                    handle.remove();
-                }
-            }
+            let captured = match ancestor {
+                Some(ref mut ancestor) => ancestor.captured.drain(..).collect(),
+                None => Vec::new(),
+            };
+            self.apply_listeners(&element, captured, env);
 
-            for mut listener in self.listeners.drain(..) {
-                let handle = listener.attach(&element, env.clone());
-                self.captured.push(handle);
-            }
+            // Start with an empty precursor, because it put childs to itself
+            apply_children(element.as_node(), None, &mut self.childs, ancestor_childs, &env);
 
-            let mut self_childs = self.childs.iter_mut().map(Some).collect::<Vec<_>>();
-            // Process children
-            let diff = self_childs.len() as i32 - ancestor_childs.len() as i32;
-            if diff > 0 {
-                for _ in 0..diff {
-                    ancestor_childs.push(None);
-                }
-            } else if diff < 0 {
-                for _ in 0..-diff {
-                    self_childs.push(None);
-                }
+            self.apply_props(&element, &mut ancestor);
+        }
+        self.reference.as_ref().map(|e| e.as_node().to_owned())
+    }
+
+    /// Binds the virtual tag to an existing `Element` with the same tag name,
+    /// attaches listeners to it and hydrates its children.
+    fn hydrate(
+        &mut self,
+        parent: &Node,
+        cursor: &mut Option<Node>,
+        env: &Scope<Self::Component>,
+    ) -> Option<Node> {
+        assert!(self.reference.is_none(), "reference is ignored so must not be set");
+        let current = cursor.take();
+        *cursor = current.as_ref().and_then(|node| node.next_sibling());
+        let element = current
+            .clone()
+            .and_then(|node| Element::try_from(node).ok())
+            .filter(|element| element.node_name().eq_ignore_ascii_case(&self.tag));
+        let element = match element {
+            Some(element) => element,
+            None => {
+                hydration_mismatch(format_args!("expected <{}>, found {:?}", self.tag, current));
+                // Render the tag from scratch in place of the found node
+                return self.apply(parent, None, current.map(VNode::VRef), env);
             }
-            // Start with an empty precursor, because it put childs to itself
-            let mut precursor = None;
-            for pair in self_childs.into_iter().zip(ancestor_childs) {
-                match pair {
-                    (Some(left), right) => {
-                        precursor =
-                            left.apply(element.as_node(), precursor.as_ref(), right, &env);
-                    }
-                    (None, Some(mut right)) => {
-                        right.detach(element.as_node());
-                    }
-                    (None, None) => {
-                        panic!("redundant iterations during diff");
-                    }
-                }
+        };
+        self.reference = Some(element.clone());
+        self.node_ref.set(Some(element.as_node().to_owned()));
+        // Classes and attributes which the server rendered differently are fixed
+        let mut adopted = Some(self.adopted(&element));
+        self.report_mismatches(&mut adopted);
+        self.apply_diffs(&element, &mut adopted);
+
+        self.apply_listeners(&element, Vec::new(), env);
+
+        let mut child_cursor = element.first_child();
+        for child in &mut self.childs {
+            child.hydrate(element.as_node(), &mut child_cursor, env);
+        }
+        // The value of `textarea` is rendered as its content
+        if !self.tag.eq_ignore_ascii_case("textarea") {
+            while let Some(node) = child_cursor {
+                hydration_mismatch(format_args!("redundant node {:?} in <{}>", node, self.tag));
+                child_cursor = node.next_sibling();
+                element.remove_child(&node).expect("can't remove redundant node");
             }
         }
+        self.apply_props(&element, &mut None);
         self.reference.as_ref().map(|e| e.as_node().to_owned())
     }
 }
 
 impl<COMP: Component> fmt::Debug for VTag<COMP> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "VTag {{ tag: {} }}", self.tag)
+        dump::Printer::new(f).tag(self)
     }
 }
 
@@ -487,11 +749,53 @@ fn set_attribute(element: &Element, name: &str, value: &str) {
     js!( @(no_return) @{element}.setAttribute( @{name}, @{value} ); );
 }
 
+/// Returns names and values of all attributes of an element.
+fn get_attributes(element: &Element) -> Vec<(String, String)> {
+    element
+        .get_attribute_names()
+        .into_iter()
+        .filter_map(|name| element.get_attribute(&name).map(|value| (name, value)))
+        .collect()
+}
+
 /// Removes attribute from a element by name.
 fn remove_attribute(element: &Element, name: &str) {
     js!( @(no_return) @{element}.removeAttribute( @{name} ); );
 }
 
+/// Sets a property of the inline style of an element.
+/// A value could end with `!important` priority.
+fn set_style(element: &Element, name: &str, value: &str) {
+    let (value, priority) = match value.find("!important") {
+        Some(position) => (value[..position].trim_end(), "important"),
+        None => (value, ""),
+    };
+    js!( @(no_return) @{element}.style.setProperty( @{name}, @{value}, @{priority} ); );
+}
+
+/// Removes a property of the inline style of an element.
+fn remove_style(element: &Element, name: &str) {
+    js!( @(no_return) @{element}.style.removeProperty( @{name} ); );
+}
+
+/// Assigns a DOM property of an element.
+fn set_property(element: &Element, name: &str, value: &PropValue) {
+    match *value {
+        PropValue::Value(ref value) => {
+            js!( @(no_return) @{element}[@{name}] = @{value}; );
+        }
+        PropValue::Json(ref json) => {
+            js!( @(no_return) @{element}[@{name}] = JSON.parse(@{json}); );
+        }
+    }
+}
+
+/// Resets a DOM property of an element to `undefined`. Built-in properties
+/// convert it to their default values.
+fn remove_property(element: &Element, name: &str) {
+    js!( @(no_return) @{element}[@{name}] = undefined; );
+}
+
 /// Set `checked` value for the `InputElement`.
 fn set_checked(input: &InputElement, value: bool) {
     js!( @(no_return) @{input}.checked = @{value}; );
@@ -511,6 +815,10 @@ impl<COMP: Component> PartialEq for VTag<COMP> {
             return false;
         }
 
+        if self.key != other.key {
+            return false;
+        }
+
         if self.checked != other.checked {
             return false;
         }
@@ -536,6 +844,14 @@ impl<COMP: Component> PartialEq for VTag<COMP> {
             return false;
         }
 
+        if self.styles != other.styles {
+            return false;
+        }
+
+        if self.props != other.props {
+            return false;
+        }
+
         if self.childs.len() != other.childs.len() {
             return false;
         }
@@ -3,9 +3,10 @@
 use std::cmp::PartialEq;
 use std::fmt;
 use std::marker::PhantomData;
+use stdweb::unstable::{TryFrom, TryInto};
 use stdweb::web::{document, INode, Node, TextNode};
 use html::{Component, Scope};
-use super::{Reform, VDiff, VNode};
+use super::{hydration_mismatch, Reform, VDiff, VNode};
 
 /// A type for a virtual
 /// [`TextNode`](https://developer.mozilla.org/en-US/docs/Web/API/Document/createTextNode)
@@ -45,12 +46,12 @@ impl<COMP: Component> VDiff for VText<COMP> {
 
     /// Renders virtual node over existent `TextNode`, but
     /// only if value of text had changed.
-    /// Parameter `precursor` is necesssary for `VTag` and `VList` which
-    /// has children and renders them.
+    /// Parameter `precursor` is used to put a new `TextNode` in the right place
+    /// when there is no ancestor to replace.
     fn apply(
         &mut self,
         parent: &Node,
-        _: Option<&Node>,
+        precursor: Option<&Node>,
         opposite: Option<VNode<Self::Component>>,
         _: &Scope<Self::Component>,
     ) -> Option<Node> {
@@ -82,6 +83,61 @@ impl<COMP: Component> VDiff for VText<COMP> {
                     parent
                         .insert_before(&element, &sibling)
                         .expect("can't insert text before sibling");
+                } else {
+                    let precursor = precursor.and_then(|before| before.next_sibling());
+                    if let Some(precursor) = precursor {
+                        parent
+                            .insert_before(&element, &precursor)
+                            .expect("can't insert text before precursor");
+                    } else {
+                        parent.append_child(&element);
+                    }
+                }
+                self.reference = Some(element);
+            }
+        }
+        self.reference.as_ref().map(|t| t.as_node().to_owned())
+    }
+
+    /// Binds the virtual node to an existing `TextNode`. Browsers join adjacent
+    /// text nodes when parse HTML, so the node is split if it contains more text.
+    fn hydrate(
+        &mut self,
+        parent: &Node,
+        cursor: &mut Option<Node>,
+        _: &Scope<Self::Component>,
+    ) -> Option<Node> {
+        assert!(self.reference.is_none(), "reference is ignored so must not be set");
+        let text_node = cursor.clone().and_then(|node| TextNode::try_from(node).ok());
+        match text_node {
+            // Empty text nodes are not rendered to HTML, they have to be created
+            Some(ref text_node) if !self.text.is_empty() => {
+                let content = text_node.node_value().unwrap_or_default();
+                if content == self.text {
+                    *cursor = text_node.next_sibling();
+                } else if content.starts_with(&self.text) {
+                    // Offset of `splitText` is measured in UTF-16 code units
+                    let offset = self.text.encode_utf16().count() as u32;
+                    let rest: TextNode = js!( return @{text_node}.splitText(@{offset}); )
+                        .try_into()
+                        .expect("can't split text node");
+                    *cursor = Some(rest.as_node().to_owned());
+                } else {
+                    hydration_mismatch(format_args!("expected text {:?}, found {:?}", self.text, content));
+                    text_node.set_node_value(Some(&self.text));
+                    *cursor = text_node.next_sibling();
+                }
+                self.reference = Some(text_node.to_owned());
+            }
+            _ => {
+                if !self.text.is_empty() {
+                    hydration_mismatch(format_args!("expected text {:?}, found {:?}", self.text, cursor));
+                }
+                let element = document().create_text_node(&self.text);
+                if let Some(ref sibling) = *cursor {
+                    parent
+                        .insert_before(&element, sibling)
+                        .expect("can't insert text before sibling");
                 } else {
                     parent.append_child(&element);
                 }
@@ -94,7 +150,7 @@ impl<COMP: Component> VDiff for VText<COMP> {
 
 impl<COMP: Component> fmt::Debug for VText<COMP> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "VText {{ text: {} }}", self.text)
+        write!(f, "{:?}", self.text)
     }
 }
 
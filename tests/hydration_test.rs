@@ -0,0 +1,83 @@
+//! Hydration needs a DOM, these tests run with `cargo web test --features web_test`.
+#![cfg(feature = "web_test")]
+
+#[macro_use]
+extern crate stdweb;
+extern crate yew;
+
+use stdweb::unstable::TryInto;
+use stdweb::web::{document, Element, IElement, INode};
+use yew::ssr::render_to_string;
+use yew::{html, App, Component, ComponentLink, Html, Renderable, ShouldRender};
+
+struct Counter {
+    value: u32,
+}
+
+impl Component for Counter {
+    type Message = ();
+    type Properties = ();
+
+    fn create(_: Self::Properties, _: ComponentLink<Self>) -> Self {
+        Counter { value: 0 }
+    }
+
+    fn update(&mut self, _: Self::Message) -> ShouldRender {
+        self.value += 1;
+        true
+    }
+}
+
+impl Renderable<Counter> for Counter {
+    fn view(&self) -> Html<Self> {
+        html! {
+            <div id="counter" class="counter">
+                <button onclick=|_| ()>{ "+1" }</button>
+                <span>{ self.value }</span>
+            </div>
+        }
+    }
+}
+
+/// Creates a detached element with the server-rendered HTML.
+fn container(html: &str) -> Element {
+    yew::initialize();
+    let element = document().create_element("div").unwrap();
+    js! { @(no_return) @{&element}.innerHTML = @{html}; }
+    element
+}
+
+fn inner_html(element: &Element) -> String {
+    js!(return @{element}.innerHTML;).try_into().unwrap()
+}
+
+#[test]
+fn hydrates_server_rendered_nodes() {
+    let html = render_to_string::<Counter>(());
+    let element = container(&html);
+    let root = element.first_child().unwrap();
+    let button = root.first_child().unwrap();
+    let _scope = App::<Counter>::new().hydrate(element.clone());
+    assert_eq!(element.first_child().as_ref(), Some(&root));
+    assert_eq!(root.first_child().as_ref(), Some(&button));
+    assert_eq!(inner_html(&element), html);
+
+    // The listener is attached to the adopted button
+    js! { @(no_return) @{&button}.click(); }
+    assert_eq!(root.last_child().and_then(|span| span.text_content()), Some("1".into()));
+}
+
+#[test]
+fn replaces_mismatched_nodes() {
+    let element = container(
+        "<div class=\"counter stale\" id=\"counter\" data-stale=\"true\"><p>+1</p><span>0</span><i></i></div>",
+    );
+    let root: Element = element.first_child().unwrap().try_into().unwrap();
+    let span = root.last_child().unwrap().previous_sibling().unwrap();
+    let _scope = App::<Counter>::new().hydrate(element.clone());
+    assert_eq!(element.first_child().as_ref(), Some(root.as_node()));
+    assert_eq!(root.get_attribute("data-stale"), None);
+    assert_eq!(root.get_attribute("class"), Some("counter".into()));
+    assert_eq!(root.last_child().as_ref(), Some(&span));
+    assert_eq!(inner_html(&element), render_to_string::<Counter>(()));
+}
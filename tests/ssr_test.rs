@@ -0,0 +1,296 @@
+#![cfg(feature = "ssr")]
+
+extern crate yew;
+
+use std::cell::Cell;
+
+use yew::{html, Children, Component, ComponentLink, Html, Properties, Renderable, ShouldRender};
+use yew::ssr::{render_node_to_string, render_to_string};
+use yew::virtual_dom::{VNode, VRaw};
+
+struct Comp;
+
+impl Component for Comp {
+    type Message = ();
+    type Properties = ();
+
+    fn create(_: Self::Properties, _: ComponentLink<Self>) -> Self {
+        Comp
+    }
+
+    fn update(&mut self, _: Self::Message) -> ShouldRender {
+        unimplemented!();
+    }
+}
+
+impl Renderable<Comp> for Comp {
+    fn view(&self) -> Html<Self> {
+        html! {
+            <ul class="list",>
+                <Item: label="first", />
+                <Item: label="<second>", />
+            </ul>
+        }
+    }
+}
+
+struct Item {
+    label: String,
+}
+
+#[derive(Properties, Clone, PartialEq)]
+struct ItemProps {
+    #[props(required)]
+    label: String,
+}
+
+impl Component for Item {
+    type Message = ();
+    type Properties = ItemProps;
+
+    fn create(props: Self::Properties, _: ComponentLink<Self>) -> Self {
+        Item { label: props.label }
+    }
+
+    fn update(&mut self, _: Self::Message) -> ShouldRender {
+        unimplemented!();
+    }
+}
+
+impl Renderable<Item> for Item {
+    fn view(&self) -> Html<Self> {
+        html! {
+            <li>{ &self.label }</li>
+        }
+    }
+}
+
+struct Card {
+    props: CardProps,
+}
+
+#[derive(Properties, Clone, PartialEq)]
+struct CardProps {
+    #[props(required)]
+    title: String,
+    children: Children,
+}
+
+impl Component for Card {
+    type Message = ();
+    type Properties = CardProps;
+
+    fn create(props: Self::Properties, _: ComponentLink<Self>) -> Self {
+        Card { props }
+    }
+
+    fn update(&mut self, _: Self::Message) -> ShouldRender {
+        unimplemented!();
+    }
+}
+
+impl Renderable<Card> for Card {
+    fn view(&self) -> Html<Self> {
+        html! {
+            <section>
+                <h1>{ &self.props.title }</h1>
+                { for self.props.children.iter() }
+            </section>
+        }
+    }
+}
+
+#[test]
+fn renders_components() {
+    assert_eq!(
+        render_to_string::<Comp>(()),
+        "<ul class=\"list\"><li>first</li><li>&lt;second&gt;</li></ul>"
+    );
+}
+
+#[test]
+fn escapes_attributes() {
+    let node: VNode<Comp> = html! {
+        <a href="/?a=1&b=\"2\"",>{ "Link & more" }</a>
+    };
+    assert_eq!(
+        render_node_to_string(&node),
+        "<a href=\"/?a=1&amp;b=&quot;2&quot;\">Link &amp; more</a>"
+    );
+}
+
+#[test]
+fn renders_inputs() {
+    let node: VNode<Comp> = html! {
+        <div>
+            <input type="checkbox", value="on", checked=true, />
+            <textarea value="<text>",></textarea>
+        </div>
+    };
+    assert_eq!(
+        render_node_to_string(&node),
+        "<div><input type=\"checkbox\" value=\"on\" checked>\
+         <textarea>&lt;text&gt;</textarea></div>"
+    );
+}
+
+#[test]
+fn renders_namespaces() {
+    let node: VNode<Comp> = html! {
+        <svg width="10",><circle r="5",></circle></svg>
+    };
+    assert_eq!(
+        render_node_to_string(&node),
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"10\"><circle r=\"5\"></circle></svg>"
+    );
+}
+
+#[test]
+fn renders_styles() {
+    let node: VNode<Comp> = html! {
+        <div style="width: 50%; color: red",></div>
+    };
+    assert_eq!(
+        render_node_to_string(&node),
+        "<div style=\"color: red; width: 50%;\"></div>"
+    );
+}
+
+#[test]
+fn renders_children_of_components() {
+    let label = "item".to_owned();
+    let node: VNode<Comp> = html! {
+        <Card: title="Title">
+            <p>{ "Content" }</p>
+            <Item: label=&label />
+        </Card>
+    };
+    assert_eq!(
+        render_node_to_string(&node),
+        "<section><h1>Title</h1><p>Content</p><li>item</li></section>"
+    );
+}
+
+struct Panel {
+    props: PanelProps,
+    opened: bool,
+}
+
+#[derive(Properties, Clone, PartialEq)]
+struct PanelProps {
+    #[props(required)]
+    title: String,
+    #[props(required)]
+    text: String,
+}
+
+impl Component for Panel {
+    type Message = ();
+    type Properties = PanelProps;
+
+    fn create(props: Self::Properties, _: ComponentLink<Self>) -> Self {
+        Panel { props, opened: false }
+    }
+
+    fn update(&mut self, _: Self::Message) -> ShouldRender {
+        self.opened = !self.opened;
+        true
+    }
+}
+
+impl Renderable<Panel> for Panel {
+    fn view(&self) -> Html<Self> {
+        html! {
+            <Card: title=&self.props.title>
+                <p onclick=|_| ()>{ &self.props.text }</p>
+                { if self.opened { "opened" } else { "closed" } }
+            </Card>
+        }
+    }
+}
+
+#[test]
+fn renders_children_with_fields_of_parent() {
+    let props = PanelProps {
+        title: "Title".into(),
+        text: "Text".into(),
+    };
+    assert_eq!(
+        render_to_string::<Panel>(props),
+        "<section><h1>Title</h1><p>Text</p>closed</section>"
+    );
+}
+
+#[test]
+fn renders_raw_html() {
+    let node: VNode<Comp> = html! {
+        <div>
+            { VRaw::new("<b>Bold</b> & <i>italic</i>") }
+            { VRaw::new("<script>alert(1)</script><p>Text</p>").sanitize(|html| html.replace("<script>alert(1)</script>", "")) }
+        </div>
+    };
+    assert_eq!(
+        render_node_to_string(&node),
+        "<div><b>Bold</b> & <i>italic</i><p>Text</p></div>"
+    );
+}
+
+#[test]
+fn compares_sanitized_raw_html() {
+    let raw = || VRaw::<Comp>::new("<p>Text</p><script></script>");
+    let strip = |html: &str| html.replace("<script></script>", "");
+    assert_eq!(raw().sanitize(strip), VRaw::new("<p>Text</p>"));
+    assert_ne!(raw(), raw().sanitize(strip));
+
+    // The sanitizer is called once, nodes compare the cleaned HTML
+    let calls = Cell::new(0);
+    let count = |html: &str| {
+        calls.set(calls.get() + 1);
+        strip(html)
+    };
+    let sanitized = raw().sanitize(count);
+    assert_eq!(sanitized, VRaw::new("<p>Text</p>"));
+    assert_eq!(sanitized, VRaw::new("<p>Text</p>"));
+    assert_eq!(render_node_to_string(&VNode::from(sanitized)), "<p>Text</p>");
+    assert_eq!(calls.get(), 1);
+}
+
+struct Counter {
+    value: u32,
+}
+
+impl Component for Counter {
+    type Message = u32;
+    type Properties = ();
+
+    fn create(_: Self::Properties, _: ComponentLink<Self>) -> Self {
+        Counter { value: 1 }
+    }
+
+    fn update(&mut self, value: Self::Message) -> ShouldRender {
+        self.value = value;
+        true
+    }
+}
+
+impl Renderable<Counter> for Counter {
+    fn view(&self) -> Html<Self> {
+        let value = self.value;
+        html! {
+            <div>
+                <button onclick=move |_| value + 1>{ "+1" }</button>
+                <span>{ self.value }</span>
+            </div>
+        }
+    }
+}
+
+/// Rendering of a view with listeners doesn't call JS, it would fail
+/// in a native test binary.
+#[test]
+fn renders_without_browser() {
+    assert_eq!(
+        render_to_string::<Counter>(()),
+        "<div><button>+1</button><span>1</span></div>"
+    );
+}
@@ -1,25 +1,21 @@
 extern crate yew;
+#[cfg(feature = "web_test")]
+extern crate log;
+#[cfg(feature = "web_test")]
+#[macro_use]
+extern crate stdweb;
 
-use yew::{html, Component, ComponentLink, Html, Renderable, ShouldRender};
+use yew::{html, Children, Component, ComponentLink, Html, Properties, Renderable, ShouldRender};
 use yew::virtual_dom::VNode;
 
 struct Comp;
 
-#[derive(PartialEq, Clone)]
+#[derive(Properties, PartialEq, Clone)]
 struct Props {
     field_1: u32,
     field_2: u32,
 }
 
-impl Default for Props {
-    fn default() -> Self {
-        Props {
-            field_1: 0,
-            field_2: 0,
-        }
-    }
-}
-
 impl Component for Comp {
     type Message = ();
     type Properties = Props;
@@ -39,6 +35,62 @@ impl Renderable<Comp> for Comp {
     }
 }
 
+struct Labeled;
+
+#[derive(Properties, PartialEq, Clone, Debug)]
+struct LabeledProps {
+    #[props(required)]
+    label: String,
+    #[props(required)]
+    count: u32,
+    hidden: bool,
+}
+
+impl Component for Labeled {
+    type Message = ();
+    type Properties = LabeledProps;
+
+    fn create(_: Self::Properties, _: ComponentLink<Self>) -> Self {
+        Labeled
+    }
+
+    fn update(&mut self, _: Self::Message) -> ShouldRender {
+        unimplemented!();
+    }
+}
+
+impl Renderable<Labeled> for Labeled {
+    fn view(&self) -> Html<Self> {
+        unimplemented!();
+    }
+}
+
+struct Card;
+
+#[derive(Properties, PartialEq, Clone)]
+struct CardProps {
+    children: Children,
+}
+
+impl Component for Card {
+    type Message = ();
+    type Properties = CardProps;
+
+    fn create(_: Self::Properties, _: ComponentLink<Self>) -> Self {
+        Card
+    }
+
+    fn update(&mut self, _: Self::Message) -> ShouldRender {
+        unimplemented!();
+    }
+}
+
+impl Renderable<Card> for Card {
+    fn view(&self) -> Html<Self> {
+        unimplemented!();
+    }
+}
+
 #[test]
 fn set_properties_to_component() {
     let _: VNode<Comp> = html! {
@@ -66,3 +118,426 @@ fn set_properties_to_component() {
         <Comp: with props, field_2=2, />
     };
 }
+
+#[test]
+fn set_key_to_component() {
+    let node: VNode<Comp> = html! {
+        <Comp: key="first", field_1=1, />
+    };
+    assert_eq!(node.key(), Some("first"));
+}
+
+#[test]
+fn set_required_properties_to_component() {
+    let _: VNode<Labeled> = html! {
+        <Labeled: label="first" count=1 />
+    };
+
+    let _: VNode<Labeled> = html! {
+        <Labeled: hidden=true count=2 label=String::from("second") />
+    };
+
+    let props = LabeledProps {
+        label: "third".into(),
+        count: 3,
+        hidden: false,
+    };
+
+    let _: VNode<Labeled> = html! {
+        <Labeled: with props, hidden=true />
+    };
+}
+
+#[test]
+fn build_properties_with_builder() {
+    let props = LabeledProps::builder().count(1).hidden(true).label("first".into()).build();
+    assert_eq!(props.label, "first");
+    assert_eq!(props.count, 1);
+    assert!(props.hidden);
+}
+
+#[derive(Properties, PartialEq, Clone)]
+struct LimitProps {
+    #[props(required)]
+    value: u32,
+    #[props(default = 10)]
+    limit: u32,
+    #[props(default = "items".into())]
+    unit: String,
+}
+
+#[test]
+fn build_properties_with_defaults() {
+    let props = LimitProps::builder().value(1).build();
+    assert_eq!(props.limit, 10);
+    assert_eq!(props.unit, "items");
+
+    let props = LimitProps::builder().limit(5).value(1).build();
+    assert_eq!(props.limit, 5);
+}
+
+#[test]
+fn compare_components() {
+    let a: VNode<Labeled> = html! {
+        <div>
+            <Labeled: label="first" count=1 />
+        </div>
+    };
+    let b: VNode<Labeled> = html! {
+        <div>
+            <Labeled: label="first" count=1 />
+        </div>
+    };
+    assert_eq!(a, b);
+
+    let c: VNode<Labeled> = html! {
+        <div>
+            <Labeled: label="first" count=2 />
+        </div>
+    };
+    assert_ne!(a, c);
+
+    let d: VNode<Labeled> = html! {
+        <div>
+            <Labeled: key="keyed" label="first" count=1 />
+        </div>
+    };
+    assert_ne!(a, d);
+
+    let e: VNode<Labeled> = html! {
+        <div>
+            <Comp: />
+        </div>
+    };
+    assert_ne!(a, e);
+}
+
+#[test]
+fn compare_children_of_components() {
+    let view = |text: &'static str| -> VNode<Card> {
+        html! {
+            <Card:>
+                <p>{ text }</p>
+            </Card>
+        }
+    };
+    assert_eq!(view("first"), view("first"));
+    assert_ne!(view("first"), view("second"));
+
+    let empty: VNode<Card> = html! { <Card: /> };
+    assert_ne!(view("first"), empty);
+}
+
+#[test]
+fn compare_children_with_listeners() {
+    let view = || -> VNode<Card> {
+        html! {
+            <Card:>
+                <button onclick=|_| ()>{ "Click" }</button>
+            </Card>
+        }
+    };
+    // Handlers can't be compared, so the component gets the new ones
+    assert_ne!(view(), view());
+}
+
+#[test]
+fn dump_components() {
+    let a: VNode<Labeled> = html! {
+        <div>
+            <Labeled: key="first" label="first" count=1 />
+            <Comp: field_1=1 />
+        </div>
+    };
+    assert_eq!(
+        format!("{:?}", a),
+        "<div><vcomp_test::Labeled: key=\"first\" LabeledProps { label: \"first\", count: 1, hidden: false } /><vcomp_test::Comp: .. /></div>",
+    );
+}
+
+/// Tests of destruction which need a DOM, they run with `cargo web test --features web_test`.
+#[cfg(feature = "web_test")]
+mod destroy {
+    use std::cell::{Cell, RefCell};
+    use stdweb::web::{document, INode};
+    use yew::{html, App, Component, ComponentLink, Html, Renderable, ShouldRender};
+
+    thread_local! {
+        static DROPPED: Cell<usize> = Cell::new(0);
+        static LINK: RefCell<Option<ComponentLink<Leaf>>> = RefCell::new(None);
+        static LOGS: RefCell<Vec<String>> = RefCell::new(Vec::new());
+    }
+
+    /// Collects warnings of the framework.
+    struct Logger;
+
+    impl log::Log for Logger {
+        fn enabled(&self, _: &log::Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &log::Record) {
+            LOGS.with(|logs| logs.borrow_mut().push(record.args().to_string()));
+        }
+
+        fn flush(&self) {}
+    }
+
+    static LOGGER: Logger = Logger;
+
+    fn logged(text: &str) -> bool {
+        LOGS.with(|logs| logs.borrow().iter().any(|log| log.contains(text)))
+    }
+
+    /// Counts dropped components.
+    struct Tracker;
+
+    impl Drop for Tracker {
+        fn drop(&mut self) {
+            DROPPED.with(|dropped| dropped.set(dropped.get() + 1));
+        }
+    }
+
+    struct Root {
+        show: bool,
+    }
+
+    impl Component for Root {
+        type Message = bool;
+        type Properties = ();
+
+        fn create(_: Self::Properties, _: ComponentLink<Self>) -> Self {
+            Root { show: false }
+        }
+
+        fn update(&mut self, show: Self::Message) -> ShouldRender {
+            self.show = show;
+            true
+        }
+    }
+
+    impl Renderable<Root> for Root {
+        fn view(&self) -> Html<Self> {
+            html! {
+                <div>{ if self.show { <Branch: /> } }</div>
+            }
+        }
+    }
+
+    /// Hides its branch in the update which queues a message to the leaf.
+    struct Closer {
+        show: bool,
+    }
+
+    impl Component for Closer {
+        type Message = ();
+        type Properties = ();
+
+        fn create(_: Self::Properties, _: ComponentLink<Self>) -> Self {
+            Closer { show: true }
+        }
+
+        fn update(&mut self, _: Self::Message) -> ShouldRender {
+            // The scheduler is busy with this update, so the message is queued
+            LINK.with(|link| link.borrow_mut().as_mut().unwrap().send_self(()));
+            self.show = false;
+            true
+        }
+    }
+
+    impl Renderable<Closer> for Closer {
+        fn view(&self) -> Html<Self> {
+            html! {
+                <div>{ if self.show { <Branch: /> } }</div>
+            }
+        }
+    }
+
+    struct Branch {
+        _tracker: Tracker,
+    }
+
+    impl Component for Branch {
+        type Message = ();
+        type Properties = ();
+
+        fn create(_: Self::Properties, _: ComponentLink<Self>) -> Self {
+            Branch { _tracker: Tracker }
+        }
+
+        fn update(&mut self, _: Self::Message) -> ShouldRender {
+            true
+        }
+    }
+
+    impl Renderable<Branch> for Branch {
+        fn view(&self) -> Html<Self> {
+            html! {
+                <p><Leaf: /></p>
+            }
+        }
+    }
+
+    struct Leaf {
+        _tracker: Tracker,
+    }
+
+    impl Component for Leaf {
+        type Message = ();
+        type Properties = ();
+
+        fn create(_: Self::Properties, link: ComponentLink<Self>) -> Self {
+            LINK.with(|cell| *cell.borrow_mut() = Some(link));
+            Leaf { _tracker: Tracker }
+        }
+
+        fn update(&mut self, _: Self::Message) -> ShouldRender {
+            true
+        }
+    }
+
+    impl Renderable<Leaf> for Leaf {
+        fn view(&self) -> Html<Self> {
+            html! {
+                <span>{ "leaf" }</span>
+            }
+        }
+    }
+
+    #[test]
+    fn drops_destroyed_components() {
+        yew::initialize();
+        let _ = log::set_logger(&LOGGER);
+        log::set_max_level(log::LevelFilter::Warn);
+        DROPPED.with(|dropped| dropped.set(0));
+        LOGS.with(|logs| logs.borrow_mut().clear());
+        let element = document().create_element("div").unwrap();
+        let mut scope = App::<Root>::new().mount(element.clone());
+        scope.send_message(true);
+        assert_eq!(element.text_content(), Some("leaf".into()));
+        assert_eq!(DROPPED.with(Cell::get), 0);
+
+        // The nested component is released with the nodes of its parent
+        scope.send_message(false);
+        assert_eq!(element.text_content(), Some("".into()));
+        assert_eq!(DROPPED.with(Cell::get), 2);
+        assert!(!logged("Node not found"));
+
+        // Messages sent to the destroyed component are counted and dropped
+        let send = || LINK.with(|link| link.borrow_mut().as_mut().unwrap().send_self(()));
+        send();
+        assert!(logged("(1 dropped)"));
+        send();
+        assert!(logged("(2 dropped)"));
+    }
+
+    #[test]
+    fn destroys_components_inside_of_updates() {
+        yew::initialize();
+        let _ = log::set_logger(&LOGGER);
+        log::set_max_level(log::LevelFilter::Warn);
+        DROPPED.with(|dropped| dropped.set(0));
+        LOGS.with(|logs| logs.borrow_mut().clear());
+        let element = document().create_element("div").unwrap();
+        let mut scope = App::<Closer>::new().mount(element.clone());
+        assert_eq!(element.text_content(), Some("leaf".into()));
+
+        // The leaf is destroyed by the render of the update before its queued message
+        scope.send_message(());
+        assert_eq!(element.text_content(), Some("".into()));
+        assert_eq!(DROPPED.with(Cell::get), 2);
+        assert!(logged("message sent to a destroyed component was ignored (1 dropped)"));
+    }
+}
+
+/// Tests of children which need a DOM, they run with `cargo web test --features web_test`.
+#[cfg(feature = "web_test")]
+mod children {
+    use stdweb::web::{document, INode, IParentNode};
+    use yew::{html, App, Children, Component, ComponentLink, Html, Properties, Renderable, ShouldRender};
+
+    struct Counter {
+        props: CounterProps,
+        clicks: u32,
+    }
+
+    #[derive(Properties, Clone, PartialEq)]
+    struct CounterProps {
+        #[props(required)]
+        label: String,
+    }
+
+    impl Component for Counter {
+        type Message = ();
+        type Properties = CounterProps;
+
+        fn create(props: Self::Properties, _: ComponentLink<Self>) -> Self {
+            Counter { props, clicks: 0 }
+        }
+
+        fn update(&mut self, _: Self::Message) -> ShouldRender {
+            self.clicks += 1;
+            true
+        }
+    }
+
+    impl Renderable<Counter> for Counter {
+        fn view(&self) -> Html<Self> {
+            html! {
+                <Frame:>
+                    <button onclick=|_| ()>{ format!("{}: {}", self.props.label, self.clicks) }</button>
+                </Frame>
+            }
+        }
+    }
+
+    struct Frame {
+        props: FrameProps,
+    }
+
+    #[derive(Properties, Clone, PartialEq)]
+    struct FrameProps {
+        children: Children,
+    }
+
+    impl Component for Frame {
+        type Message = ();
+        type Properties = FrameProps;
+
+        fn create(props: Self::Properties, _: ComponentLink<Self>) -> Self {
+            Frame { props }
+        }
+
+        fn update(&mut self, _: Self::Message) -> ShouldRender {
+            unimplemented!();
+        }
+
+        fn change(&mut self, props: Self::Properties) -> ShouldRender {
+            self.props = props;
+            true
+        }
+    }
+
+    impl Renderable<Frame> for Frame {
+        fn view(&self) -> Html<Self> {
+            html! {
+                <div class="frame">{ for self.props.children.iter() }</div>
+            }
+        }
+    }
+
+    #[test]
+    fn sends_messages_of_children_to_parent() {
+        yew::initialize();
+        let element = document().create_element("div").unwrap();
+        let props = CounterProps { label: "Clicks".into() };
+        let _scope = App::<Counter>::new().mount_with_props(element.clone(), props);
+        let button = element.query_selector("button").unwrap().unwrap();
+        assert_eq!(button.text_content(), Some("Clicks: 0".into()));
+
+        // The parent renders new children and the child diffs them with the button
+        js! { @(no_return) @{&button}.click(); }
+        assert_eq!(element.query_selector("button").unwrap().as_ref(), Some(&button));
+        assert_eq!(button.text_content(), Some("Clicks: 1".into()));
+    }
+}
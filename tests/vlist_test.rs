@@ -1,4 +1,6 @@
 extern crate yew;
+#[cfg(feature = "web_test")]
+extern crate stdweb;
 
 use yew::{html, Component, ComponentLink, Html, Renderable, ShouldRender};
 use yew::virtual_dom::VNode;
@@ -36,3 +38,350 @@ fn check_fragments() {
         </div>
     };
 }
+
+#[test]
+fn check_keyed_fragments() {
+    let fragment: VNode<Comp> = html! {
+        <key="row",>
+            <td></td>
+        </>
+    };
+    assert_eq!(fragment.key(), Some("row"));
+}
+
+#[test]
+fn check_conditions() {
+    let flag = true;
+    let value = Some(3);
+    let a: VNode<Comp> = html! {
+        <div>
+            { if flag { <span></span> } }
+            { if !flag { <span></span> } else { <p></p> } }
+            { if let Some(value) = value { <b>{ value }</b> } }
+            { if flag && value.is_none() { } else if flag { <i></i> } }
+            { match value {
+                Some(1) | Some(2) => { <span></span> },
+                Some(ref x) if *x > 2 => { <p>{ x }</p> }
+                _ => { "nothing" }
+            } }
+        </div>
+    };
+    if let VNode::VTag(vtag) = a {
+        let tags = vtag.childs.iter().map(|child| match child {
+            VNode::VTag(vtag) => vtag.tag().to_owned(),
+            VNode::VList(vlist) => format!("list({})", vlist.childs.len()),
+            _ => "other".to_owned(),
+        }).collect::<Vec<_>>();
+        assert_eq!(tags, vec!["span", "p", "b", "i", "p"]);
+    } else {
+        panic!("vtag expected");
+    }
+
+    let b: VNode<Comp> = html! {
+        { if !flag { <span></span> } }
+    };
+    if let VNode::VList(vlist) = b {
+        assert!(vlist.childs.is_empty());
+    } else {
+        panic!("vlist expected");
+    }
+}
+
+#[test]
+fn check_multiple_root_nodes() {
+    let flag = true;
+    let a: VNode<Comp> = html! {
+        <dt>{ "term" }</dt>
+        <dd>{ "definition" }</dd>
+        { if flag { <span></span><span></span> } }
+    };
+    if let VNode::VList(vlist) = a {
+        assert_eq!(vlist.childs.len(), 3);
+        match vlist.childs[2] {
+            VNode::VList(ref branch) => assert_eq!(branch.childs.len(), 2),
+            _ => panic!("vlist expected"),
+        }
+    } else {
+        panic!("vlist expected");
+    }
+
+    let b: VNode<Comp> = html! {
+        <dt>{ "term" }</dt>
+    };
+    if let VNode::VTag(vtag) = b {
+        assert_eq!(vtag.tag(), "dt");
+    } else {
+        panic!("vtag expected");
+    }
+}
+
+#[test]
+fn compare_fragments() {
+    let a: VNode<Comp> = html! {
+        <>
+            <p>{ "first" }</p>
+            <p>{ "second" }</p>
+        </>
+    };
+    let b: VNode<Comp> = html! {
+        <>
+            <p>{ "first" }</p>
+            <p>{ "second" }</p>
+        </>
+    };
+    assert_eq!(a, b);
+
+    let c: VNode<Comp> = html! {
+        <>
+            <p>{ "first" }</p>
+            <p>{ "third" }</p>
+        </>
+    };
+    assert_ne!(a, c);
+
+    let d: VNode<Comp> = html! {
+        <key="keyed",>
+            <p>{ "first" }</p>
+            <p>{ "second" }</p>
+        </>
+    };
+    assert_ne!(a, d);
+}
+
+/// Tests of the keyed diff which need a DOM, they run with `cargo web test --features web_test`.
+#[cfg(feature = "web_test")]
+mod keyed {
+    use stdweb::web::{document, Element, INode, Node};
+    use yew::html::Scope;
+    use yew::{html, App, Component, ComponentLink, Html, Renderable, ShouldRender};
+
+    /// Renders rows with keys, rows which names start with `_` have no key.
+    struct Rows {
+        names: Vec<&'static str>,
+    }
+
+    impl Component for Rows {
+        type Message = Vec<&'static str>;
+        type Properties = ();
+
+        fn create(_: Self::Properties, _: ComponentLink<Self>) -> Self {
+            Rows { names: Vec::new() }
+        }
+
+        fn update(&mut self, names: Self::Message) -> ShouldRender {
+            self.names = names;
+            true
+        }
+    }
+
+    impl Renderable<Rows> for Rows {
+        fn view(&self) -> Html<Self> {
+            let row = |name: &&'static str| {
+                if name.starts_with('_') {
+                    html! { <li>{ name }</li> }
+                } else {
+                    html! { <li key=name>{ name }</li> }
+                }
+            };
+            html! {
+                <ul>{ for self.names.iter().map(row) }</ul>
+            }
+        }
+    }
+
+    fn mount(names: Vec<&'static str>) -> (Scope<Rows>, Element) {
+        yew::initialize();
+        let element = document().create_element("div").unwrap();
+        let mut scope = App::<Rows>::new().mount(element.clone());
+        scope.send_message(names);
+        (scope, element)
+    }
+
+    /// Returns the names and nodes of the rendered rows.
+    fn rows(element: &Element) -> Vec<(String, Node)> {
+        let list = element.first_child().expect("list is not rendered");
+        list.child_nodes()
+            .iter()
+            .filter(|node| node.node_name() == "LI")
+            .map(|node| (node.text_content().unwrap_or_default(), node))
+            .collect()
+    }
+
+    fn names(rows: &[(String, Node)]) -> Vec<&str> {
+        rows.iter().map(|row| row.0.as_str()).collect()
+    }
+
+    fn node<'a>(rows: &'a [(String, Node)], name: &str) -> &'a Node {
+        &rows.iter().find(|row| row.0 == name).expect("row is not rendered").1
+    }
+
+    #[test]
+    fn moves_keyed_children() {
+        let (mut scope, element) = mount(vec!["a", "b", "c"]);
+        let before = rows(&element);
+        scope.send_message(vec!["c", "a", "b"]);
+        let after = rows(&element);
+        assert_eq!(names(&after), vec!["c", "a", "b"]);
+        for name in &["a", "b", "c"] {
+            assert_eq!(node(&before, name), node(&after, name));
+        }
+    }
+
+    #[test]
+    fn inserts_and_removes_keyed_children() {
+        let (mut scope, element) = mount(vec!["a", "b", "c"]);
+        let before = rows(&element);
+        let removed = node(&before, "c").clone();
+        scope.send_message(vec!["b", "d", "a"]);
+        let after = rows(&element);
+        assert_eq!(names(&after), vec!["b", "d", "a"]);
+        assert_eq!(node(&before, "a"), node(&after, "a"));
+        assert_eq!(node(&before, "b"), node(&after, "b"));
+        assert!(removed.parent_node().is_none());
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "duplicate key of siblings: a")]
+    fn panics_on_duplicate_keys() {
+        let (mut scope, _element) = mount(vec!["a", "b"]);
+        scope.send_message(vec!["b", "a", "a"]);
+    }
+
+    #[test]
+    fn pairs_unkeyed_children_by_order() {
+        let (mut scope, element) = mount(vec!["_x", "a", "_y", "b"]);
+        let before = rows(&element);
+        scope.send_message(vec!["b", "_z", "a"]);
+        let after = rows(&element);
+        assert_eq!(names(&after), vec!["b", "_z", "a"]);
+        assert_eq!(node(&before, "a"), node(&after, "a"));
+        assert_eq!(node(&before, "b"), node(&after, "b"));
+        // The first unkeyed row is reused for the first unkeyed row of the next render
+        assert_eq!(node(&before, "_x"), node(&after, "_z"));
+        assert!(node(&before, "_y").parent_node().is_none());
+    }
+}
+
+/// Tests of conditions which need a DOM, they run with `cargo web test --features web_test`.
+#[cfg(feature = "web_test")]
+mod conditions {
+    use stdweb::web::{document, INode, IParentNode};
+    use yew::{html, App, Component, ComponentLink, Html, Renderable, ShouldRender};
+
+    struct Badge {
+        show: bool,
+    }
+
+    impl Component for Badge {
+        type Message = bool;
+        type Properties = ();
+
+        fn create(_: Self::Properties, _: ComponentLink<Self>) -> Self {
+            Badge { show: false }
+        }
+
+        fn update(&mut self, show: Self::Message) -> ShouldRender {
+            self.show = show;
+            true
+        }
+    }
+
+    impl Renderable<Badge> for Badge {
+        fn view(&self) -> Html<Self> {
+            html! {
+                <div>
+                    { if self.show { <b></b> } }
+                    <p></p>
+                </div>
+            }
+        }
+    }
+
+    #[test]
+    fn marks_empty_first_branch_with_text_node() {
+        yew::initialize();
+        let element = document().create_element("div").unwrap();
+        let mut scope = App::<Badge>::new().mount(element.clone());
+        let div = element.first_child().expect("badge is not rendered");
+        // The marker is a child node, but it's not an element for CSS selectors
+        assert_eq!(div.child_nodes().len(), 2);
+        assert_eq!(div.first_child().and_then(|marker| marker.text_content()), Some("".into()));
+        assert!(element.query_selector("p:first-child").unwrap().is_some());
+
+        scope.send_message(true);
+        assert_eq!(div.child_nodes().len(), 2);
+        assert!(element.query_selector("b:first-child").unwrap().is_some());
+    }
+}
+
+/// Tests of foreign nodes in lists which need a DOM, they run with `cargo web test --features web_test`.
+#[cfg(feature = "web_test")]
+mod foreign {
+    use std::cell::Cell;
+    use stdweb::web::{document, Element, INode, Node};
+    use yew::virtual_dom::VForeign;
+    use yew::{html, App, Callback, Component, ComponentLink, Html, Renderable, ShouldRender};
+
+    thread_local! {
+        static DETACHED: Cell<usize> = Cell::new(0);
+    }
+
+    /// Renders paragraphs before an embedded node, the node is moved without a key.
+    struct Embed {
+        node: Element,
+        before: Option<usize>,
+    }
+
+    impl Component for Embed {
+        type Message = Option<usize>;
+        type Properties = ();
+
+        fn create(_: Self::Properties, _: ComponentLink<Self>) -> Self {
+            let node = document().create_element("canvas").unwrap();
+            Embed { node, before: Some(0) }
+        }
+
+        fn update(&mut self, before: Self::Message) -> ShouldRender {
+            self.before = before;
+            true
+        }
+    }
+
+    impl Renderable<Embed> for Embed {
+        fn view(&self) -> Html<Self> {
+            let mut nodes: Vec<Html<Self>> = Vec::new();
+            if let Some(before) = self.before {
+                for _ in 0..before {
+                    nodes.push(html! { <p></p> });
+                }
+                let ondetach = Callback::from(|_| DETACHED.with(|detached| detached.set(detached.get() + 1)));
+                nodes.push(VForeign::new(&self.node).ondetach(ondetach).into());
+            }
+            html! {
+                <div>{ for nodes }</div>
+            }
+        }
+    }
+
+    #[test]
+    fn moves_foreign_nodes_without_notification() {
+        yew::initialize();
+        let element = document().create_element("div").unwrap();
+        let mut scope = App::<Embed>::new().mount(element.clone());
+        let list = element.first_child().unwrap();
+        let node: Node = list.last_child().unwrap();
+
+        // The node is detached and embedded again by the same render
+        scope.send_message(Some(2));
+        assert_eq!(list.last_child().as_ref(), Some(&node));
+        // The node is embedded and then its previous place is detached
+        scope.send_message(Some(0));
+        assert_eq!(list.first_child().as_ref(), Some(&node));
+        assert_eq!(DETACHED.with(Cell::get), 0);
+
+        scope.send_message(None);
+        assert!(node.parent_node().is_none());
+        assert_eq!(DETACHED.with(Cell::get), 1);
+    }
+}
@@ -1,7 +1,13 @@
+#[cfg(feature = "web_test")]
+#[macro_use]
+extern crate stdweb;
 extern crate yew;
 
-use yew::{html, Component, ComponentLink, Html, Renderable, ShouldRender};
-use yew::virtual_dom::VNode;
+use std::collections::HashMap;
+use yew::{classes, html, Classes, Component, ComponentLink, Html, NodeRef, Renderable, ShouldRender, Style};
+use yew::events::{ClickEvent, CustomEvent, InputData};
+use yew::format::Json;
+use yew::virtual_dom::{PropValue, VNode};
 
 struct Comp;
 
@@ -221,6 +227,45 @@ fn it_compares_checked() {
     assert_ne!(a, c);
 }
 
+#[test]
+fn it_compares_keys() {
+    let a: VNode<Comp> = html! {
+        <li key=1,></li>
+    };
+
+    let b: VNode<Comp> = html! {
+        <li key=1,></li>
+    };
+
+    let c: VNode<Comp> = html! {
+        <li key="other",></li>
+    };
+
+    assert_eq!(a, b);
+    assert_ne!(a, c);
+    assert_eq!(a.key(), Some("1"));
+    if let VNode::VTag(vtag) = a {
+        assert!(!vtag.attributes.contains_key("key"));
+    } else {
+        panic!("vtag expected");
+    }
+}
+
+#[test]
+fn it_sets_node_ref() {
+    let node_ref = NodeRef::default();
+    let a: VNode<Comp> = html! {
+        <input ref=node_ref.clone(), />
+    };
+
+    if let VNode::VTag(vtag) = a {
+        assert_eq!(vtag.node_ref, node_ref);
+        assert!(vtag.node_ref.get().is_none());
+    } else {
+        panic!("vtag expected");
+    }
+}
+
 #[test]
 fn it_allows_aria_attributes() {
     let a: VNode<Comp> = html! {
@@ -254,3 +299,418 @@ fn it_allows_aria_attributes() {
         panic!("vtag expected");
     }
 }
+
+#[test]
+fn it_allows_listener_modifiers() {
+    let a: VNode<Comp> = html! {
+        <form onsubmit.prevent=|_| (), onclick.stop.capture=|_| (), onscroll.passive=|_| (),>
+            <input oninput=|_| (), />
+        </form>
+    };
+    if let VNode::VTag(vtag) = a {
+        let kinds = vtag.listeners.iter().map(|listener| listener.kind()).collect::<Vec<_>>();
+        assert_eq!(kinds, vec!["onsubmit", "onclick", "onscroll"]);
+    } else {
+        panic!("vtag expected");
+    }
+}
+
+#[test]
+fn it_allows_listeners_with_any_handler() {
+    fn on_click(_: ClickEvent) {}
+    let on_input = |_: InputData| ();
+    let a: VNode<Comp> = html! {
+        <div onclick=on_click (ondoubleclick)=|_| () (oninput)=on_input></div>
+    };
+    if let VNode::VTag(vtag) = a {
+        let kinds = vtag.listeners.iter().map(|listener| listener.kind()).collect::<Vec<_>>();
+        assert_eq!(kinds, vec!["onclick", "ondoubleclick", "oninput"]);
+    } else {
+        panic!("vtag expected");
+    }
+}
+
+#[test]
+fn it_keeps_attributes_which_start_with_on() {
+    let a: VNode<Comp> = html! {
+        <animate one="1" onbegin="start()" />
+    };
+    if let VNode::VTag(vtag) = a {
+        assert!(vtag.listeners.is_empty());
+        assert_eq!(vtag.attributes.get("one"), Some(&"1".to_owned()));
+        assert_eq!(vtag.attributes.get("onbegin"), Some(&"start()".to_owned()));
+    } else {
+        panic!("vtag expected");
+    }
+}
+
+#[test]
+fn it_allows_touch_and_media_listeners() {
+    let a: VNode<Comp> = html! {
+        <div ontouchstart=|_| (), ontouchend=|_| (), onmousewheel.passive=|_| (),
+             onanimationend=|_| (), ontransitionend=|_| (),
+             oncopy=|_| (), onpaste.prevent=|_| (), oncompositionend=|_| (),>
+            <img onload=|_| (), onerror=|_| (), />
+        </div>
+    };
+    if let VNode::VTag(vtag) = a {
+        assert_eq!(vtag.listeners.len(), 8);
+    } else {
+        panic!("vtag expected");
+    }
+}
+
+#[test]
+fn it_allows_custom_event_listeners() {
+    let a: VNode<Comp> = html! {
+        <my-slider on:value-changed=|e: CustomEvent| { let _ = e; }, on:reset.stop=|_| (),>
+        </my-slider>
+    };
+    if let VNode::VTag(vtag) = a {
+        let kinds = vtag.listeners.iter().map(|listener| listener.kind()).collect::<Vec<_>>();
+        assert_eq!(vtag.tag(), "my-slider");
+        assert_eq!(kinds, vec!["value-changed", "reset"]);
+    } else {
+        panic!("vtag expected");
+    }
+}
+
+#[test]
+fn it_skips_optional_and_false_attributes() {
+    let title: Option<&str> = None;
+    let placeholder = Some("Name");
+    let a: VNode<Comp> = html! {
+        <input title=title, placeholder=placeholder, disabled=false, readonly=true,
+               hidden=false, aria-hidden="false", data-state="false", data-id=Some(5), />
+    };
+    if let VNode::VTag(vtag) = a {
+        assert!(!vtag.attributes.contains_key("title"));
+        assert!(!vtag.attributes.contains_key("disabled"));
+        assert!(!vtag.attributes.contains_key("hidden"));
+        assert_eq!(vtag.attributes.get("placeholder"), Some(&"Name".to_owned()));
+        assert_eq!(vtag.attributes.get("readonly"), Some(&"".to_owned()));
+        assert_eq!(vtag.attributes.get("aria-hidden"), Some(&"false".to_owned()));
+        assert_eq!(vtag.attributes.get("data-state"), Some(&"false".to_owned()));
+        assert_eq!(vtag.attributes.get("data-id"), Some(&"5".to_owned()));
+    } else {
+        panic!("vtag expected");
+    }
+}
+
+#[test]
+fn supports_conditional_classes() {
+    let active = true;
+    let disabled = false;
+    let extra: Option<&str> = Some("extra");
+    let missing: Option<String> = None;
+    let list = vec!["list-1", "list-2"];
+    let a: VNode<Comp> = html! {
+        <div class=classes!("btn btn-primary", (active, "active"), (disabled, "disabled"), extra, missing, list),></div>
+    };
+    let b: VNode<Comp> = html! {
+        <div class="btn-primary btn active extra list-1 list-2",></div>
+    };
+    assert_eq!(a, b);
+
+    let iterated: Classes = (1..3).map(|index| format!("item-{}", index)).collect();
+    let c: VNode<Comp> = html! {
+        <div class=("base", iterated, (active, "active")),></div>
+    };
+    if let VNode::VTag(vtag) = c {
+        assert_eq!(vtag.classes.len(), 4);
+        assert!(vtag.classes.contains("item-2"));
+    } else {
+        panic!("vtag expected");
+    }
+
+    let mut classes = classes!["one"];
+    classes.push(classes!("two", "one"));
+    assert_eq!(classes.to_string(), "one two");
+}
+
+#[test]
+fn it_sets_styles() {
+    let width = 40;
+    let a: VNode<Comp> = html! {
+        <div style="color: red; width: 40%;",></div>
+    };
+    let b: VNode<Comp> = html! {
+        <div style=Style::new().set("width", format!("{}%", width)).set("color", "red"),></div>
+    };
+    let mut map = HashMap::new();
+    map.insert("color", "red");
+    map.insert("width", "40%");
+    let c: VNode<Comp> = html! {
+        <div style=map,></div>
+    };
+    let d: VNode<Comp> = html! {
+        <div style="color: blue; width: 40%",></div>
+    };
+    assert_eq!(a, b);
+    assert_eq!(a, c);
+    assert_ne!(a, d);
+
+    if let VNode::VTag(vtag) = a {
+        assert!(!vtag.attributes.contains_key("style"));
+        assert_eq!(vtag.styles.get("width"), Some(&"40%".to_owned()));
+    } else {
+        panic!("vtag expected");
+    }
+
+    let mut style = Style::from("margin: 0; color: red !important");
+    style.insert("margin", "1px");
+    assert_eq!(style.get("color"), Some("red !important"));
+    assert_eq!(style.remove("color"), Some("red !important".to_owned()));
+    assert_eq!(style.to_string(), "margin: 1px;");
+}
+
+#[test]
+fn it_sets_properties() {
+    let a: VNode<Comp> = html! {
+        <input type="checkbox", prop:indeterminate=true, prop:tabIndex=2,/>
+    };
+    let b: VNode<Comp> = html! {
+        <input type="checkbox", props=vec![("indeterminate", true)], prop:tabIndex=2,/>
+    };
+    let c: VNode<Comp> = html! {
+        <input type="checkbox", prop:indeterminate=false, prop:tabIndex=2,/>
+    };
+    assert_eq!(a, b);
+    assert_ne!(a, c);
+
+    let rows = vec![(1, "first"), (2, "second")];
+    let d: VNode<Comp> = html! {
+        <data-grid prop:rows=Json(&rows), prop:caption="Rows",></data-grid>
+    };
+    if let VNode::VTag(vtag) = d {
+        assert!(vtag.attributes.is_empty());
+        assert_eq!(vtag.props.get("rows"), Some(&PropValue::Json("[[1,\"first\"],[2,\"second\"]]".into())));
+        assert_eq!(vtag.props.get("caption"), Some(&PropValue::from("Rows")));
+    } else {
+        panic!("vtag expected");
+    }
+}
+
+#[test]
+fn it_allows_attributes_without_commas() {
+    let width = 10;
+    let a: VNode<Comp> = html! {
+        <div id="item" data-width=width aria-hidden="true" onclick=|_| () class=("a", "b")></div>
+    };
+    let b: VNode<Comp> = html! {
+        <div id="item", data-width=width, aria-hidden="true", onclick=|_| (), class=("a", "b"),></div>
+    };
+    assert_eq!(a, b);
+
+    let c: VNode<Comp> = html! {
+        <input value=width.to_string() disabled={ width > 5 } />
+    };
+    if let VNode::VTag(vtag) = c {
+        assert_eq!(vtag.value, Some("10".into()));
+        assert_eq!(vtag.attributes.get("disabled"), Some(&"".to_owned()));
+    } else {
+        panic!("vtag expected");
+    }
+}
+
+#[test]
+fn dump_tags() {
+    let a: VNode<Comp> = html! {
+        <ul id="list" class=("b", "a") style="color: red" onclick=|_| ()>
+            <li>{ "first" }</li>
+            <>
+                <li key="second" />
+            </>
+        </ul>
+    };
+    assert_eq!(
+        format!("{:?}", a),
+        "<ul class=\"a b\" id=\"list\" style=\"color: red\" onclick=..><li>\"first\"</li><><li key=\"second\" /></></ul>",
+    );
+    assert_eq!(format!("{:#?}", a), r#"<ul class="a b" id="list" style="color: red" onclick=..>
+    <li>
+        "first"
+    </li>
+    <>
+        <li key="second" />
+    </>
+</ul>"#);
+}
+
+/// Tests of delegated listeners which need a DOM, they run with `cargo web test --features web_test`.
+#[cfg(feature = "web_test")]
+mod delegation {
+    use std::cell::RefCell;
+    use stdweb::web::{document, Element, INode, Node};
+    use yew::html::Scope;
+    use yew::virtual_dom::VPortal;
+    use yew::{html, App, Component, ComponentLink, Html, Renderable, ShouldRender};
+
+    thread_local! {
+        static CALLS: RefCell<Vec<&'static str>> = RefCell::new(Vec::new());
+        static HOST: Element = document().create_element("div").unwrap();
+    }
+
+    enum Msg {
+        Call(&'static str),
+        Simplify,
+    }
+
+    struct Button {
+        custom: bool,
+    }
+
+    impl Component for Button {
+        type Message = Msg;
+        type Properties = ();
+
+        fn create(_: Self::Properties, _: ComponentLink<Self>) -> Self {
+            Button { custom: true }
+        }
+
+        fn update(&mut self, msg: Self::Message) -> ShouldRender {
+            match msg {
+                Msg::Call(name) => {
+                    CALLS.with(|calls| calls.borrow_mut().push(name));
+                    false
+                }
+                Msg::Simplify => {
+                    self.custom = false;
+                    true
+                }
+            }
+        }
+    }
+
+    impl Renderable<Button> for Button {
+        fn view(&self) -> Html<Self> {
+            if self.custom {
+                html! {
+                    <div>
+                        <button onclick=|_| Msg::Call("onclick") on:click=|_| Msg::Call("on:click")></button>
+                    </div>
+                }
+            } else {
+                html! {
+                    <div>
+                        <button onclick=|_| Msg::Call("onclick")></button>
+                    </div>
+                }
+            }
+        }
+    }
+
+    struct Slider;
+
+    impl Component for Slider {
+        type Message = Msg;
+        type Properties = ();
+
+        fn create(_: Self::Properties, _: ComponentLink<Self>) -> Self {
+            Slider
+        }
+
+        fn update(&mut self, msg: Self::Message) -> ShouldRender {
+            if let Msg::Call(name) = msg {
+                CALLS.with(|calls| calls.borrow_mut().push(name));
+            }
+            false
+        }
+    }
+
+    impl Renderable<Slider> for Slider {
+        fn view(&self) -> Html<Self> {
+            html! {
+                <div>
+                    <my-slider on:value-changed=|_| Msg::Call("on:value-changed")></my-slider>
+                </div>
+            }
+        }
+    }
+
+    struct Modal {
+        host: Element,
+    }
+
+    impl Component for Modal {
+        type Message = Msg;
+        type Properties = ();
+
+        fn create(_: Self::Properties, _: ComponentLink<Self>) -> Self {
+            Modal {
+                host: HOST.with(Element::clone),
+            }
+        }
+
+        fn update(&mut self, msg: Self::Message) -> ShouldRender {
+            if let Msg::Call(name) = msg {
+                CALLS.with(|calls| calls.borrow_mut().push(name));
+            }
+            false
+        }
+    }
+
+    impl Renderable<Modal> for Modal {
+        fn view(&self) -> Html<Self> {
+            let modal = html! {
+                <button onclick=|_| Msg::Call("onclick")></button>
+            };
+            html! {
+                <div>
+                    { VPortal::new(self.host.clone(), modal) }
+                </div>
+            }
+        }
+    }
+
+    fn mount<COMP>() -> (Scope<COMP>, Element)
+    where
+        COMP: Component<Properties = ()> + Renderable<COMP>,
+    {
+        yew::initialize();
+        CALLS.with(|calls| calls.borrow_mut().clear());
+        let element = document().create_element("div").unwrap();
+        let scope = App::<COMP>::new().with_event_delegation().mount(element.clone());
+        (scope, element)
+    }
+
+    fn target(element: &Element) -> Node {
+        element.first_child().and_then(|div| div.first_child()).expect("target is not rendered")
+    }
+
+    fn calls() -> Vec<&'static str> {
+        CALLS.with(|calls| calls.borrow_mut().drain(..).collect())
+    }
+
+    #[test]
+    fn calls_listeners_of_the_same_event() {
+        let (mut scope, element) = mount::<Button>();
+        // The custom listener is added to the element and runs before the delegated one
+        js! { @(no_return) @{target(&element)}.click(); }
+        assert_eq!(calls(), vec!["on:click", "onclick"]);
+
+        // The remaining listener is kept when the other one is removed
+        scope.send_message(Msg::Simplify);
+        js! { @(no_return) @{target(&element)}.click(); }
+        assert_eq!(calls(), vec!["onclick"]);
+    }
+
+    #[test]
+    fn calls_listeners_of_non_bubbling_custom_events() {
+        let (_scope, element) = mount::<Slider>();
+        js! { @(no_return)
+            @{target(&element)}.dispatchEvent(new CustomEvent("value-changed", { bubbles: false }));
+        }
+        assert_eq!(calls(), vec!["on:value-changed"]);
+    }
+
+    #[test]
+    fn delegates_listeners_of_portals_to_the_host() {
+        let (_scope, _element) = mount::<Modal>();
+        let host = HOST.with(Element::clone);
+        let button = host.first_child().expect("node of the portal is not rendered");
+        // The host is out of the mount element, so the event reaches the registry of the portal
+        js! { @(no_return) @{button}.click(); }
+        assert_eq!(calls(), vec!["onclick"]);
+    }
+}